@@ -0,0 +1,14 @@
+/// Regenerate the C header into `OUT_DIR`; `tests/capi.rs` checks it against `include/lotus.h`.
+fn main() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).unwrap();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{crate_dir}/src/lib.rs"))
+        .generate()
+        .expect("cbindgen failed")
+        .write_to_file(format!("{out_dir}/lotus.h"));
+}
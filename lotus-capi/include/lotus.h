@@ -0,0 +1,142 @@
+/* Lotus C API. Generated by cbindgen from lotus-capi/src/lib.rs; do not edit. */
+
+#ifndef LOTUS_H
+#define LOTUS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Result code of every exported function.
+typedef enum LotusStatus {
+  LOTUS_STATUS_OK = 0,
+  LOTUS_STATUS_JUMPSTARTER_OVERFLOW = 1,
+  LOTUS_STATUS_UNEXPECTED_EOF = 2,
+  LOTUS_STATUS_INVALID_ENCODING = 3,
+  LOTUS_STATUS_VALUE_TOO_LARGE = 4,
+  // The output buffer is too small; `written` holds the required length.
+  LOTUS_STATUS_BUFFER_TOO_SMALL = 5,
+  LOTUS_STATUS_NULL_POINTER = 6,
+  LOTUS_STATUS_OTHER = 7,
+  LOTUS_STATUS_CHECKSUM_MISMATCH = 8,
+} LotusStatus;
+
+// Opaque streaming reader; create with `lotus_reader_new`, release with `lotus_reader_free`.
+typedef struct LotusReader LotusReader;
+
+// Opaque streaming writer; create with `lotus_writer_new`, release with `lotus_writer_free`.
+typedef struct LotusWriter LotusWriter;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Encode one value as a byte-aligned codeword into `out`.
+//
+// # Safety
+// `out` must be valid for `out_len` writes and `written` for one write.
+enum LotusStatus lotus_encode_u64(uint64_t value,
+                                  size_t j_bits,
+                                  size_t tiers,
+                                  uint8_t *out,
+                                  size_t out_len,
+                                  size_t *written);
+
+// Decode one codeword from the start of `data`; `bits_consumed` may be null.
+//
+// # Safety
+// `data` must be valid for `len` reads, `value` for one write and `bits_consumed`, if non-null,
+// for one write.
+enum LotusStatus lotus_decode_u64(const uint8_t *data,
+                                  size_t len,
+                                  size_t j_bits,
+                                  size_t tiers,
+                                  uint64_t *value,
+                                  size_t *bits_consumed);
+
+// Encode `count` values as one packed bitstream, zero-padded to a whole byte.
+//
+// # Safety
+// `values` must be valid for `count` reads, `out` for `out_len` writes and `written` for one
+// write.
+enum LotusStatus lotus_encode_u64_slice(const uint64_t *values,
+                                        size_t count,
+                                        size_t j_bits,
+                                        size_t tiers,
+                                        uint8_t *out,
+                                        size_t out_len,
+                                        size_t *written);
+
+// Decode exactly `count` values from a packed bitstream; `bits_consumed` may be null.
+//
+// # Safety
+// `data` must be valid for `len` reads, `values` for `count` writes and `bits_consumed`, if
+// non-null, for one write.
+enum LotusStatus lotus_decode_u64_slice(const uint8_t *data,
+                                        size_t len,
+                                        size_t j_bits,
+                                        size_t tiers,
+                                        uint64_t *values,
+                                        size_t count,
+                                        size_t *bits_consumed);
+
+// Create a streaming writer for the given configuration.
+struct LotusWriter *lotus_writer_new(size_t j_bits, size_t tiers);
+
+// Append one codeword; nothing is written on error.
+//
+// # Safety
+// `writer` must come from `lotus_writer_new` and not have been freed.
+enum LotusStatus lotus_writer_push(struct LotusWriter *writer, uint64_t value);
+
+// Number of bits pushed so far, or 0 for a null handle.
+//
+// # Safety
+// `writer` must be null or come from `lotus_writer_new` and not have been freed.
+size_t lotus_writer_bits(const struct LotusWriter *writer);
+
+// Copy the stream written so far, zero-padded to a whole byte; the writer stays usable.
+//
+// # Safety
+// `writer` must come from `lotus_writer_new` and not have been freed; `out` must be valid for
+// `out_len` writes and `written` for one write.
+enum LotusStatus lotus_writer_finish(const struct LotusWriter *writer,
+                                     uint8_t *out,
+                                     size_t out_len,
+                                     size_t *written);
+
+// Release a writer; null is ignored.
+//
+// # Safety
+// `writer` must be null or come from `lotus_writer_new` and not have been freed.
+void lotus_writer_free(struct LotusWriter *writer);
+
+// Create a streaming reader over a copy of `data`; returns null if `data` is null and `len > 0`.
+//
+// # Safety
+// `data` must be valid for `len` reads.
+struct LotusReader *lotus_reader_new(const uint8_t *data, size_t len, size_t j_bits, size_t tiers);
+
+// Decode the next codeword; on error the reader does not advance.
+//
+// # Safety
+// `reader` must come from `lotus_reader_new` and not have been freed; `value` must be valid for
+// one write.
+enum LotusStatus lotus_reader_next(struct LotusReader *reader, uint64_t *value);
+
+// Number of bits consumed so far, or 0 for a null handle.
+//
+// # Safety
+// `reader` must be null or come from `lotus_reader_new` and not have been freed.
+size_t lotus_reader_bits_consumed(const struct LotusReader *reader);
+
+// Release a reader; null is ignored.
+//
+// # Safety
+// `reader` must be null or come from `lotus_reader_new` and not have been freed.
+void lotus_reader_free(struct LotusReader *reader);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LOTUS_H */
@@ -0,0 +1,448 @@
+//! C ABI for the `lotus` crate, built as `liblotus_capi` (static and shared). The header in
+//! `include/lotus.h` is generated from this file by cbindgen.
+//!
+//! Every function returns a [`LotusStatus`]; outputs go through caller-provided pointers, which
+//! are left untouched unless the status is `LOTUS_STATUS_OK` (or `LOTUS_STATUS_BUFFER_TOO_SMALL`
+//! for the `written` length). Byte buffers are never retained: readers copy their input.
+
+use std::slice;
+
+use lotus::{BitReader, BitWriter, LotusError, lotus_read_u64, lotus_write_u64};
+
+/// Result code of every exported function.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LotusStatus {
+    Ok = 0,
+    JumpstarterOverflow = 1,
+    UnexpectedEof = 2,
+    InvalidEncoding = 3,
+    ValueTooLarge = 4,
+    /// The output buffer is too small; `written` holds the required length.
+    BufferTooSmall = 5,
+    NullPointer = 6,
+    Other = 7,
+    ChecksumMismatch = 8,
+}
+
+impl From<LotusError> for LotusStatus {
+    fn from(err: LotusError) -> Self {
+        match err {
+            LotusError::JumpstarterOverflow => Self::JumpstarterOverflow,
+            LotusError::UnexpectedEof => Self::UnexpectedEof,
+            LotusError::InvalidEncoding => Self::InvalidEncoding,
+            LotusError::ValueTooLarge => Self::ValueTooLarge,
+            LotusError::ChecksumMismatch => Self::ChecksumMismatch,
+            LotusError::Message(_) | LotusError::Io { .. } => Self::Other,
+        }
+    }
+}
+
+/// Opaque streaming writer; create with `lotus_writer_new`, release with `lotus_writer_free`.
+pub struct LotusWriter {
+    writer: BitWriter,
+    j_bits: usize,
+    tiers: usize,
+}
+
+/// Opaque streaming reader; create with `lotus_reader_new`, release with `lotus_reader_free`.
+pub struct LotusReader {
+    bytes: Vec<u8>,
+    bit_pos: usize,
+    j_bits: usize,
+    tiers: usize,
+}
+
+macro_rules! try_status {
+    ($expr:expr) => {
+        match $expr {
+            Ok(value) => value,
+            Err(err) => return LotusStatus::from(err),
+        }
+    };
+}
+
+/// View `len` elements at `ptr`, treating a null pointer as an empty slice only when `len == 0`.
+///
+/// # Safety
+/// A non-null `ptr` must be valid for reads of `len` elements.
+unsafe fn input<'a, T>(ptr: *const T, len: usize) -> Option<&'a [T]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        // SAFETY: non-null and valid for `len` reads per the caller's contract.
+        Some(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+}
+
+/// Copy `bytes` into `out` and store the length in `written`, reporting a short buffer.
+///
+/// # Safety
+/// `written` must be valid for writes; a non-null `out` must be valid for `out_len` writes.
+unsafe fn copy_out(bytes: &[u8], out: *mut u8, out_len: usize, written: *mut usize) -> LotusStatus {
+    if written.is_null() {
+        return LotusStatus::NullPointer;
+    }
+    // SAFETY: checked non-null above; validity is the caller's contract.
+    unsafe { *written = bytes.len() };
+    if bytes.len() > out_len {
+        return LotusStatus::BufferTooSmall;
+    }
+    if bytes.is_empty() {
+        return LotusStatus::Ok;
+    }
+    if out.is_null() {
+        return LotusStatus::NullPointer;
+    }
+    // SAFETY: `out` is non-null, valid for `out_len >= bytes.len()` writes and cannot overlap
+    // `bytes`, which is owned by Rust.
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len()) };
+    LotusStatus::Ok
+}
+
+/// Encode one value as a byte-aligned codeword into `out`.
+///
+/// # Safety
+/// `out` must be valid for `out_len` writes and `written` for one write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_encode_u64(
+    value: u64,
+    j_bits: usize,
+    tiers: usize,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> LotusStatus {
+    let bytes = try_status!(lotus::lotus_encode_u64(value, j_bits, tiers));
+    // SAFETY: forwarded caller contract.
+    unsafe { copy_out(&bytes, out, out_len, written) }
+}
+
+/// Decode one codeword from the start of `data`; `bits_consumed` may be null.
+///
+/// # Safety
+/// `data` must be valid for `len` reads, `value` for one write and `bits_consumed`, if non-null,
+/// for one write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_decode_u64(
+    data: *const u8,
+    len: usize,
+    j_bits: usize,
+    tiers: usize,
+    value: *mut u64,
+    bits_consumed: *mut usize,
+) -> LotusStatus {
+    // SAFETY: forwarded caller contract.
+    let Some(data) = (unsafe { input(data, len) }) else {
+        return LotusStatus::NullPointer;
+    };
+    if value.is_null() {
+        return LotusStatus::NullPointer;
+    }
+    let (decoded, bits) = try_status!(lotus::lotus_decode_u64(data, j_bits, tiers));
+    // SAFETY: `value` checked non-null; `bits_consumed` only written when non-null.
+    unsafe {
+        *value = decoded;
+        if !bits_consumed.is_null() {
+            *bits_consumed = bits;
+        }
+    }
+    LotusStatus::Ok
+}
+
+/// Encode `count` values as one packed bitstream, zero-padded to a whole byte.
+///
+/// # Safety
+/// `values` must be valid for `count` reads, `out` for `out_len` writes and `written` for one
+/// write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_encode_u64_slice(
+    values: *const u64,
+    count: usize,
+    j_bits: usize,
+    tiers: usize,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> LotusStatus {
+    // SAFETY: forwarded caller contract.
+    let Some(values) = (unsafe { input(values, count) }) else {
+        return LotusStatus::NullPointer;
+    };
+    let mut writer = BitWriter::new();
+    for &value in values {
+        try_status!(lotus_write_u64(&mut writer, value, j_bits, tiers));
+    }
+    // SAFETY: forwarded caller contract.
+    unsafe { copy_out(&writer.into_bytes(), out, out_len, written) }
+}
+
+/// Decode exactly `count` values from a packed bitstream; `bits_consumed` may be null.
+///
+/// # Safety
+/// `data` must be valid for `len` reads, `values` for `count` writes and `bits_consumed`, if
+/// non-null, for one write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_decode_u64_slice(
+    data: *const u8,
+    len: usize,
+    j_bits: usize,
+    tiers: usize,
+    values: *mut u64,
+    count: usize,
+    bits_consumed: *mut usize,
+) -> LotusStatus {
+    // SAFETY: forwarded caller contract.
+    let Some(data) = (unsafe { input(data, len) }) else {
+        return LotusStatus::NullPointer;
+    };
+    if count > 0 && values.is_null() {
+        return LotusStatus::NullPointer;
+    }
+    let mut reader = BitReader::new(data);
+    let mut decoded = Vec::with_capacity(count.min(len * 8));
+    for _ in 0..count {
+        decoded.push(try_status!(lotus_read_u64(&mut reader, j_bits, tiers)));
+    }
+    // SAFETY: `values` is valid for `count` writes when `count > 0`; `bits_consumed` only
+    // written when non-null.
+    unsafe {
+        if count > 0 {
+            std::ptr::copy_nonoverlapping(decoded.as_ptr(), values, count);
+        }
+        if !bits_consumed.is_null() {
+            *bits_consumed = reader.bits_consumed();
+        }
+    }
+    LotusStatus::Ok
+}
+
+/// Create a streaming writer for the given configuration.
+#[unsafe(no_mangle)]
+pub extern "C" fn lotus_writer_new(j_bits: usize, tiers: usize) -> *mut LotusWriter {
+    Box::into_raw(Box::new(LotusWriter {
+        writer: BitWriter::new(),
+        j_bits,
+        tiers,
+    }))
+}
+
+/// Append one codeword; nothing is written on error.
+///
+/// # Safety
+/// `writer` must come from `lotus_writer_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_writer_push(writer: *mut LotusWriter, value: u64) -> LotusStatus {
+    // SAFETY: caller contract; null is rejected by `as_mut`.
+    let Some(writer) = (unsafe { writer.as_mut() }) else {
+        return LotusStatus::NullPointer;
+    };
+    try_status!(lotus_write_u64(
+        &mut writer.writer,
+        value,
+        writer.j_bits,
+        writer.tiers
+    ));
+    LotusStatus::Ok
+}
+
+/// Number of bits pushed so far, or 0 for a null handle.
+///
+/// # Safety
+/// `writer` must be null or come from `lotus_writer_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_writer_bits(writer: *const LotusWriter) -> usize {
+    // SAFETY: caller contract.
+    unsafe { writer.as_ref() }.map_or(0, |writer| writer.writer.bits_written())
+}
+
+/// Copy the stream written so far, zero-padded to a whole byte; the writer stays usable.
+///
+/// # Safety
+/// `writer` must come from `lotus_writer_new` and not have been freed; `out` must be valid for
+/// `out_len` writes and `written` for one write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_writer_finish(
+    writer: *const LotusWriter,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> LotusStatus {
+    // SAFETY: caller contract.
+    let Some(writer) = (unsafe { writer.as_ref() }) else {
+        return LotusStatus::NullPointer;
+    };
+    let bytes = writer.writer.clone().into_bytes();
+    // SAFETY: forwarded caller contract.
+    unsafe { copy_out(&bytes, out, out_len, written) }
+}
+
+/// Release a writer; null is ignored.
+///
+/// # Safety
+/// `writer` must be null or come from `lotus_writer_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_writer_free(writer: *mut LotusWriter) {
+    if !writer.is_null() {
+        // SAFETY: allocated by `lotus_writer_new` and freed at most once per caller contract.
+        drop(unsafe { Box::from_raw(writer) });
+    }
+}
+
+/// Create a streaming reader over a copy of `data`; returns null if `data` is null and `len > 0`.
+///
+/// # Safety
+/// `data` must be valid for `len` reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_reader_new(
+    data: *const u8,
+    len: usize,
+    j_bits: usize,
+    tiers: usize,
+) -> *mut LotusReader {
+    // SAFETY: forwarded caller contract.
+    let Some(data) = (unsafe { input(data, len) }) else {
+        return std::ptr::null_mut();
+    };
+    Box::into_raw(Box::new(LotusReader {
+        bytes: data.to_vec(),
+        bit_pos: 0,
+        j_bits,
+        tiers,
+    }))
+}
+
+/// Decode the next codeword; on error the reader does not advance.
+///
+/// # Safety
+/// `reader` must come from `lotus_reader_new` and not have been freed; `value` must be valid for
+/// one write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_reader_next(
+    reader: *mut LotusReader,
+    value: *mut u64,
+) -> LotusStatus {
+    // SAFETY: caller contract.
+    let Some(reader) = (unsafe { reader.as_mut() }) else {
+        return LotusStatus::NullPointer;
+    };
+    if value.is_null() {
+        return LotusStatus::NullPointer;
+    }
+    let offset = reader.bit_pos % 8;
+    let mut bits = BitReader::new(&reader.bytes[reader.bit_pos / 8..]);
+    try_status!(bits.read_bits(offset));
+    let decoded = try_status!(lotus_read_u64(&mut bits, reader.j_bits, reader.tiers));
+    reader.bit_pos += bits.bits_consumed() - offset;
+    // SAFETY: checked non-null above.
+    unsafe { *value = decoded };
+    LotusStatus::Ok
+}
+
+/// Number of bits consumed so far, or 0 for a null handle.
+///
+/// # Safety
+/// `reader` must be null or come from `lotus_reader_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_reader_bits_consumed(reader: *const LotusReader) -> usize {
+    // SAFETY: caller contract.
+    unsafe { reader.as_ref() }.map_or(0, |reader| reader.bit_pos)
+}
+
+/// Release a reader; null is ignored.
+///
+/// # Safety
+/// `reader` must be null or come from `lotus_reader_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lotus_reader_free(reader: *mut LotusReader) {
+    if !reader.is_null() {
+        // SAFETY: allocated by `lotus_reader_new` and freed at most once per caller contract.
+        drop(unsafe { Box::from_raw(reader) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_round_trip_and_short_buffer() {
+        let values = [0u64, 1, 1_000, u64::MAX];
+        let mut written = 0usize;
+        let status = unsafe {
+            lotus_encode_u64_slice(
+                values.as_ptr(),
+                4,
+                3,
+                1,
+                std::ptr::null_mut(),
+                0,
+                &mut written,
+            )
+        };
+        assert_eq!(status, LotusStatus::BufferTooSmall);
+        let mut out = vec![0u8; written];
+        let status = unsafe {
+            lotus_encode_u64_slice(
+                values.as_ptr(),
+                4,
+                3,
+                1,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(status, LotusStatus::Ok);
+
+        let mut decoded = [0u64; 4];
+        let status = unsafe {
+            lotus_decode_u64_slice(
+                out.as_ptr(),
+                out.len(),
+                3,
+                1,
+                decoded.as_mut_ptr(),
+                4,
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(status, LotusStatus::Ok);
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn streaming_handles() {
+        unsafe {
+            let writer = lotus_writer_new(2, 1);
+            for value in 0..50 {
+                assert_eq!(lotus_writer_push(writer, value), LotusStatus::Ok);
+            }
+            assert_eq!(
+                lotus_writer_push(writer, u64::MAX),
+                LotusStatus::ValueTooLarge
+            );
+            let mut out = [0u8; 128];
+            let mut written = 0;
+            assert_eq!(
+                lotus_writer_finish(writer, out.as_mut_ptr(), out.len(), &mut written),
+                LotusStatus::Ok
+            );
+            assert_eq!(written, lotus_writer_bits(writer).div_ceil(8));
+            lotus_writer_free(writer);
+
+            let reader = lotus_reader_new(out.as_ptr(), written, 2, 1);
+            let mut value = 0;
+            for expected in 0..50 {
+                assert_eq!(lotus_reader_next(reader, &mut value), LotusStatus::Ok);
+                assert_eq!(value, expected);
+            }
+            let consumed = lotus_reader_bits_consumed(reader);
+            assert_ne!(lotus_reader_next(reader, &mut value), LotusStatus::Ok);
+            assert_eq!(lotus_reader_bits_consumed(reader), consumed);
+            lotus_reader_free(reader);
+        }
+    }
+}
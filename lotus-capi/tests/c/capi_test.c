@@ -0,0 +1,95 @@
+/* Exercises the C API through include/lotus.h; run by tests/capi.rs. */
+#include <stdio.h>
+#include <string.h>
+
+#include "lotus.h"
+
+#define CHECK(cond)                                                   \
+    do {                                                              \
+        if (!(cond)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,    \
+                    __LINE__, #cond);                                 \
+            return 1;                                                 \
+        }                                                             \
+    } while (0)
+
+static int single_values(void) {
+    const uint64_t values[] = {0, 1, 2, 300, 65535, UINT64_MAX};
+    for (size_t i = 0; i < sizeof values / sizeof values[0]; i++) {
+        uint8_t buf[32];
+        size_t written = 0;
+        CHECK(lotus_encode_u64(values[i], 3, 1, buf, sizeof buf, &written) ==
+              LOTUS_STATUS_OK);
+        uint64_t decoded = 0;
+        size_t bits = 0;
+        CHECK(lotus_decode_u64(buf, written, 3, 1, &decoded, &bits) ==
+              LOTUS_STATUS_OK);
+        CHECK(decoded == values[i]);
+        CHECK((bits + 7) / 8 == written);
+    }
+    uint8_t tiny[1];
+    size_t needed = 0;
+    CHECK(lotus_encode_u64(UINT64_MAX, 3, 1, tiny, sizeof tiny, &needed) ==
+          LOTUS_STATUS_BUFFER_TOO_SMALL);
+    CHECK(needed > sizeof tiny);
+    CHECK(lotus_encode_u64(UINT64_MAX, 1, 1, tiny, sizeof tiny, &needed) ==
+          LOTUS_STATUS_VALUE_TOO_LARGE);
+    return 0;
+}
+
+static int slices(void) {
+    uint64_t values[100];
+    for (size_t i = 0; i < 100; i++) {
+        values[i] = i * i * 31;
+    }
+    size_t needed = 0;
+    CHECK(lotus_encode_u64_slice(values, 100, 2, 1, NULL, 0, &needed) ==
+          LOTUS_STATUS_BUFFER_TOO_SMALL);
+    uint8_t buf[1024];
+    CHECK(needed <= sizeof buf);
+    size_t written = 0;
+    CHECK(lotus_encode_u64_slice(values, 100, 2, 1, buf, sizeof buf,
+                                 &written) == LOTUS_STATUS_OK);
+    CHECK(written == needed);
+    uint64_t decoded[100];
+    CHECK(lotus_decode_u64_slice(buf, written, 2, 1, decoded, 100, NULL) ==
+          LOTUS_STATUS_OK);
+    CHECK(memcmp(values, decoded, sizeof values) == 0);
+    CHECK(lotus_decode_u64_slice(buf, written / 2, 2, 1, decoded, 100,
+                                 NULL) == LOTUS_STATUS_UNEXPECTED_EOF);
+    return 0;
+}
+
+static int streaming(void) {
+    LotusWriter *writer = lotus_writer_new(1, 2);
+    CHECK(writer != NULL);
+    for (uint64_t v = 0; v < 1000; v += 7) {
+        CHECK(lotus_writer_push(writer, v) == LOTUS_STATUS_OK);
+    }
+    uint8_t buf[4096];
+    size_t written = 0;
+    CHECK(lotus_writer_finish(writer, buf, sizeof buf, &written) ==
+          LOTUS_STATUS_OK);
+    CHECK(written == (lotus_writer_bits(writer) + 7) / 8);
+    lotus_writer_free(writer);
+
+    LotusReader *reader = lotus_reader_new(buf, written, 1, 2);
+    CHECK(reader != NULL);
+    uint64_t value = 0;
+    for (uint64_t v = 0; v < 1000; v += 7) {
+        CHECK(lotus_reader_next(reader, &value) == LOTUS_STATUS_OK);
+        CHECK(value == v);
+    }
+    CHECK(lotus_reader_next(NULL, &value) == LOTUS_STATUS_NULL_POINTER);
+    lotus_reader_free(reader);
+    lotus_reader_free(NULL);
+    return 0;
+}
+
+int main(void) {
+    if (single_values() || slices() || streaming()) {
+        return 1;
+    }
+    puts("capi ok");
+    return 0;
+}
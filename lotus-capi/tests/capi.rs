@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn checked_in_header_is_current() {
+    let generated = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/lotus.h")).unwrap();
+    let checked_in = std::fs::read_to_string(manifest_dir().join("include/lotus.h")).unwrap();
+    assert!(
+        generated == checked_in,
+        "include/lotus.h is stale; copy the header generated in OUT_DIR over it"
+    );
+}
+
+/// Directory holding `liblotus_capi.a`: the parent of the `deps` directory this test runs from.
+fn artifact_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    exe.parent().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn c_program_links_and_passes() {
+    let staticlib = artifact_dir().join("liblotus_capi.a");
+    assert!(staticlib.exists(), "missing {}", staticlib.display());
+    let out_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let binary = out_dir.join("capi_test");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(&compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir().join("include"))
+        .arg(manifest_dir().join("tests/c/capi_test.c"))
+        .arg(&staticlib)
+        .args(["-lpthread", "-ldl", "-lm"])
+        .arg("-o")
+        .arg(&binary)
+        .status();
+    let status = match status {
+        Ok(status) => status,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            eprintln!("skipping: C compiler `{compiler}` not found");
+            return;
+        }
+        Err(err) => panic!("failed to run {compiler}: {err}"),
+    };
+    assert!(status.success(), "compiling tests/c/capi_test.c failed");
+    let output = Command::new(&binary).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "capi ok");
+}
@@ -0,0 +1,387 @@
+//! Derive macros for the `lotus` crate's `LotusEncode` and `LotusDecode` traits.
+//!
+//! Fields are written in declaration order with the configuration passed to `lotus_encode`.
+//! Enums prefix their fields with the variant's declaration index as a Lotus codeword.
+//! Field behaviour can be adjusted with `#[lotus(...)]`:
+//!
+//! * `config = "J2D1"`: code the field with a fixed `(J, d)` configuration.
+//! * `zigzag`: convert the integer field through `i64` and write it zigzag-mapped.
+//! * `raw = N`: write an unsigned integer field as exactly `N` raw bits (1 to 64).
+//! * `skip`: leave the field out of the stream; decoding fills it with `Default::default()`.
+//! * `skip, default = "path"`: as `skip`, but decoding calls `path()` instead.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Field, Fields, GenericParam, Generics, Ident, LitInt, LitStr, Path,
+    parse_macro_input, parse_quote,
+};
+
+#[proc_macro_derive(LotusEncode, attributes(lotus))]
+pub fn derive_lotus_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(LotusDecode, attributes(lotus))]
+pub fn derive_lotus_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// How a single field is laid out in the stream.
+enum FieldMode {
+    Nested,
+    Zigzag,
+    Raw(usize),
+    Skip(Option<Path>),
+}
+
+struct FieldAttrs {
+    config: Option<(usize, usize)>,
+    mode: FieldMode,
+}
+
+fn parse_config(lit: &LitStr) -> syn::Result<(usize, usize)> {
+    let text = lit.value().to_ascii_uppercase();
+    let parsed = text
+        .strip_prefix('J')
+        .and_then(|rest| rest.split_once('D'))
+        .and_then(|(j, d)| Some((j.parse::<usize>().ok()?, d.parse::<usize>().ok()?)));
+    match parsed {
+        Some((j_bits, tiers)) if (1..=8).contains(&j_bits) && tiers >= 1 => Ok((j_bits, tiers)),
+        _ => Err(syn::Error::new(
+            lit.span(),
+            "expected a configuration like \"J2D1\" with J in 1..=8 and d >= 1",
+        )),
+    }
+}
+
+fn parse_field_attrs(field: &Field) -> syn::Result<FieldAttrs> {
+    let mut config = None;
+    let mut zigzag = false;
+    let mut raw = None;
+    let mut skip = false;
+    let mut default = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("lotus"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("config") {
+                config = Some(parse_config(&meta.value()?.parse::<LitStr>()?)?);
+            } else if meta.path.is_ident("zigzag") {
+                zigzag = true;
+            } else if meta.path.is_ident("raw") {
+                let lit: LitInt = meta.value()?.parse()?;
+                let width: usize = lit.base10_parse()?;
+                if !(1..=64).contains(&width) {
+                    return Err(syn::Error::new(lit.span(), "raw width must be in 1..=64"));
+                }
+                raw = Some(width);
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+            } else {
+                return Err(meta.error("unknown lotus attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    let span = field
+        .ident
+        .as_ref()
+        .map_or_else(Span::call_site, Ident::span);
+    if default.is_some() && !skip {
+        return Err(syn::Error::new(span, "`default` requires `skip`"));
+    }
+    let mode = match (zigzag, raw, skip) {
+        (false, None, false) => FieldMode::Nested,
+        (true, None, false) => FieldMode::Zigzag,
+        (false, Some(width), false) => FieldMode::Raw(width),
+        (false, None, true) => FieldMode::Skip(default),
+        _ => {
+            return Err(syn::Error::new(
+                span,
+                "`zigzag`, `raw` and `skip` are mutually exclusive",
+            ));
+        }
+    };
+    if config.is_some() && matches!(mode, FieldMode::Raw(_) | FieldMode::Skip(_)) {
+        return Err(syn::Error::new(
+            span,
+            "`config` has no effect on `raw` or `skip` fields",
+        ));
+    }
+    Ok(FieldAttrs { config, mode })
+}
+
+fn field_attrs(fields: &Fields) -> syn::Result<Vec<FieldAttrs>> {
+    fields.iter().map(parse_field_attrs).collect()
+}
+
+fn add_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(#bound));
+        }
+    }
+    generics
+}
+
+/// Shadow `j_bits`/`tiers` with a field's fixed configuration, if it has one.
+fn config_prelude(attrs: &FieldAttrs) -> TokenStream2 {
+    match attrs.config {
+        Some((j, d)) => quote! { let (j_bits, tiers): (usize, usize) = (#j, #d); },
+        None => quote! {},
+    }
+}
+
+/// Statements writing one field, given an expression of type `&FieldType`.
+fn encode_field(value: &TokenStream2, attrs: &FieldAttrs) -> TokenStream2 {
+    let prelude = config_prelude(attrs);
+    let range_check = match attrs.mode {
+        FieldMode::Raw(width) if width < 64 => quote! {
+            if raw >> #width != 0 {
+                return ::core::result::Result::Err(::lotus::LotusError::ValueTooLarge);
+            }
+        },
+        _ => quote! {},
+    };
+    let body = match &attrs.mode {
+        FieldMode::Nested => quote! {
+            ::lotus::LotusEncode::lotus_encode(#value, writer, j_bits, tiers)?;
+        },
+        FieldMode::Zigzag => quote! {
+            let signed: i64 = ::core::convert::TryFrom::try_from(*#value)
+                .map_err(|_| ::lotus::LotusError::ValueTooLarge)?;
+            ::lotus::lotus_write_u64(writer, ::lotus::zigzag_encode(signed), j_bits, tiers)?;
+        },
+        FieldMode::Raw(width) => quote! {
+            let raw: u64 = ::core::convert::TryFrom::try_from(*#value)
+                .map_err(|_| ::lotus::LotusError::ValueTooLarge)?;
+            #range_check
+            writer.write_bits(raw, #width)?;
+        },
+        FieldMode::Skip(_) => return quote! {},
+    };
+    quote! { { #prelude #body } }
+}
+
+/// Expression reading one field of type `ty`.
+fn decode_field(ty: &syn::Type, attrs: &FieldAttrs) -> TokenStream2 {
+    let prelude = config_prelude(attrs);
+    let body = match &attrs.mode {
+        FieldMode::Nested => quote! {
+            <#ty as ::lotus::LotusDecode>::lotus_decode(reader, j_bits, tiers)?
+        },
+        FieldMode::Zigzag => quote! {
+            <#ty as ::core::convert::TryFrom<i64>>::try_from(::lotus::zigzag_decode(
+                ::lotus::lotus_read_u64(reader, j_bits, tiers)?,
+            ))
+            .map_err(|_| ::lotus::LotusError::ValueTooLarge)?
+        },
+        FieldMode::Raw(width) => quote! {
+            <#ty as ::core::convert::TryFrom<u64>>::try_from(reader.read_bits(#width)?)
+                .map_err(|_| ::lotus::LotusError::ValueTooLarge)?
+        },
+        FieldMode::Skip(None) => return quote! { ::core::default::Default::default() },
+        FieldMode::Skip(Some(path)) => return quote! { #path() },
+    };
+    quote! { { #prelude #body } }
+}
+
+/// Pattern binding every non-skipped field of a variant, plus the bound names.
+///
+/// Bindings use synthetic names so fields cannot shadow `writer`, `j_bits` or `tiers`.
+fn variant_pattern(
+    fields: &Fields,
+    attrs: &[FieldAttrs],
+) -> (TokenStream2, Vec<Option<TokenStream2>>) {
+    let bindings: Vec<Option<Ident>> = attrs
+        .iter()
+        .enumerate()
+        .map(|(index, attrs)| match attrs.mode {
+            FieldMode::Skip(_) => None,
+            _ => Some(format_ident!("__field{}", index)),
+        })
+        .collect();
+    let pattern = match fields {
+        Fields::Named(_) => {
+            let entries = fields.iter().zip(&bindings).map(|(field, binding)| {
+                let name = &field.ident;
+                match binding {
+                    Some(binding) => quote! { #name: #binding },
+                    None => quote! { #name: _ },
+                }
+            });
+            quote! { { #(#entries),* } }
+        }
+        Fields::Unnamed(_) => {
+            let entries = bindings.iter().map(|binding| match binding {
+                Some(binding) => quote! { #binding },
+                None => quote! { _ },
+            });
+            quote! { ( #(#entries),* ) }
+        }
+        Fields::Unit => quote! {},
+    };
+    let values = bindings
+        .into_iter()
+        .map(|binding| binding.map(|binding| quote! { #binding }))
+        .collect();
+    (pattern, values)
+}
+
+/// Constructor expression for a struct or variant at `path`.
+fn construct(path: TokenStream2, fields: &Fields, attrs: &[FieldAttrs]) -> TokenStream2 {
+    let values = fields
+        .iter()
+        .zip(attrs)
+        .map(|(field, attrs)| decode_field(&field.ty, attrs));
+    match fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|field| &field.ident);
+            quote! { #path { #(#names: #values),* } }
+        }
+        Fields::Unnamed(_) => quote! { #path ( #(#values),* ) },
+        Fields::Unit => path,
+    }
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let attrs = field_attrs(&data.fields)?;
+            let writes =
+                data.fields
+                    .iter()
+                    .zip(&attrs)
+                    .enumerate()
+                    .map(|(index, (field, attrs))| {
+                        let member = match &field.ident {
+                            Some(ident) => quote! { #ident },
+                            None => {
+                                let index = syn::Index::from(index);
+                                quote! { #index }
+                            }
+                        };
+                        encode_field(&quote! { &self.#member }, attrs)
+                    });
+            quote! { #(#writes)* }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(index, variant)| {
+                    let ident = &variant.ident;
+                    let attrs = field_attrs(&variant.fields)?;
+                    let (pattern, values) = variant_pattern(&variant.fields, &attrs);
+                    let writes = values.iter().zip(&attrs).filter_map(|(value, attrs)| {
+                        value.as_ref().map(|value| encode_field(value, attrs))
+                    });
+                    let index = index as u64;
+                    Ok(quote! {
+                        Self::#ident #pattern => {
+                            ::lotus::lotus_write_u64(writer, #index, j_bits, tiers)?;
+                            #(#writes)*
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            if arms.is_empty() {
+                quote! { match *self {} }
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "LotusEncode cannot be derived for unions",
+            ));
+        }
+    };
+    let generics = add_bounds(&input.generics, quote! { ::lotus::LotusEncode });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::lotus::LotusEncode for #name #ty_generics #where_clause {
+            fn lotus_encode(
+                &self,
+                writer: &mut ::lotus::BitWriter,
+                j_bits: usize,
+                tiers: usize,
+            ) -> ::core::result::Result<(), ::lotus::LotusError> {
+                #body
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let attrs = field_attrs(&data.fields)?;
+            let value = construct(quote! { Self }, &data.fields, &attrs);
+            quote! { ::core::result::Result::Ok(#value) }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(index, variant)| {
+                    let ident = &variant.ident;
+                    let attrs = field_attrs(&variant.fields)?;
+                    let value = construct(quote! { Self::#ident }, &variant.fields, &attrs);
+                    let index = index as u64;
+                    Ok(quote! { #index => ::core::result::Result::Ok(#value), })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match ::lotus::lotus_read_u64(reader, j_bits, tiers)? {
+                    #(#arms)*
+                    _ => ::core::result::Result::Err(::lotus::LotusError::InvalidEncoding),
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "LotusDecode cannot be derived for unions",
+            ));
+        }
+    };
+    let generics = add_bounds(&input.generics, quote! { ::lotus::LotusDecode });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::lotus::LotusDecode for #name #ty_generics #where_clause {
+            fn lotus_decode(
+                reader: &mut ::lotus::BitReader<'_>,
+                j_bits: usize,
+                tiers: usize,
+            ) -> ::core::result::Result<Self, ::lotus::LotusError> {
+                #body
+            }
+        }
+    })
+}
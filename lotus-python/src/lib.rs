@@ -0,0 +1,195 @@
+//! PyO3 bindings, importable from Python as the `lotus` extension module.
+//!
+//! Bulk functions accept any buffer of unsigned 64-bit integers (`array.array('Q')`, a NumPy
+//! `uint64` array, a `memoryview`) and return decoded values as `array.array('Q')`, which NumPy
+//! can wrap without copying via `numpy.frombuffer(..., dtype=numpy.uint64)`.
+
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use lotus::{
+    BitReader, BitWriter, LOTUS_J1D2, LOTUS_J2D1, LOTUS_J3D1, lotus_encoded_bits, lotus_read_u64,
+    lotus_write_u64,
+};
+
+pyo3::create_exception!(
+    lotus,
+    LotusError,
+    PyValueError,
+    "Raised for Lotus encoding errors."
+);
+
+fn py_err(err: lotus::LotusError) -> PyErr {
+    LotusError::new_err(err.to_string())
+}
+
+/// A `(J, d)` configuration: jumpstarter bits and tier count.
+#[pyclass(frozen, eq, hash, module = "lotus", name = "Config")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Config {
+    j_bits: usize,
+    tiers: usize,
+}
+
+impl Config {
+    fn from_tuple((j_bits, tiers): (usize, usize)) -> Self {
+        Self { j_bits, tiers }
+    }
+}
+
+#[pymethods]
+impl Config {
+    #[new]
+    fn new(j_bits: usize, tiers: usize) -> PyResult<Self> {
+        if !(1..=8).contains(&j_bits) || tiers == 0 {
+            return Err(PyValueError::new_err(
+                "j_bits must be in 1..=8 and tiers at least 1",
+            ));
+        }
+        Ok(Self { j_bits, tiers })
+    }
+
+    #[classattr]
+    #[pyo3(name = "J2D1")]
+    fn j2d1() -> Self {
+        Self::from_tuple(LOTUS_J2D1)
+    }
+
+    #[classattr]
+    #[pyo3(name = "J1D2")]
+    fn j1d2() -> Self {
+        Self::from_tuple(LOTUS_J1D2)
+    }
+
+    #[classattr]
+    #[pyo3(name = "J3D1")]
+    fn j3d1() -> Self {
+        Self::from_tuple(LOTUS_J3D1)
+    }
+
+    #[getter]
+    fn j_bits(&self) -> usize {
+        self.j_bits
+    }
+
+    #[getter]
+    fn tiers(&self) -> usize {
+        self.tiers
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Config(j_bits={}, tiers={})", self.j_bits, self.tiers)
+    }
+}
+
+fn resolve(config: Option<&Bound<'_, Config>>) -> (usize, usize) {
+    config.map_or(LOTUS_J3D1, |config| {
+        let config = config.get();
+        (config.j_bits, config.tiers)
+    })
+}
+
+fn values_from_buffer(py: Python<'_>, values: &Bound<'_, PyAny>) -> PyResult<Vec<u64>> {
+    let buffer = PyBuffer::<u64>::get(values)
+        .map_err(|_| PyTypeError::new_err("expected a buffer of unsigned 64-bit integers"))?;
+    buffer.to_vec(py)
+}
+
+/// Encode one integer as a byte-aligned codeword (default config `J3D1`).
+#[pyfunction]
+#[pyo3(signature = (value, config = None))]
+fn encode<'py>(
+    py: Python<'py>,
+    value: u64,
+    config: Option<&Bound<'py, Config>>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let (j_bits, tiers) = resolve(config);
+    let bytes = lotus::lotus_encode_u64(value, j_bits, tiers).map_err(py_err)?;
+    Ok(PyBytes::new(py, &bytes))
+}
+
+/// Decode the codeword at the start of `data`, returning `(value, bits_consumed)`.
+#[pyfunction]
+#[pyo3(signature = (data, config = None))]
+fn decode(data: &[u8], config: Option<&Bound<'_, Config>>) -> PyResult<(u64, usize)> {
+    let (j_bits, tiers) = resolve(config);
+    lotus::lotus_decode_u64(data, j_bits, tiers).map_err(py_err)
+}
+
+/// Exact codeword length in bits for `value`.
+#[pyfunction]
+#[pyo3(signature = (value, config = None))]
+fn encoded_bits(value: u64, config: Option<&Bound<'_, Config>>) -> PyResult<usize> {
+    let (j_bits, tiers) = resolve(config);
+    lotus_encoded_bits(value, j_bits, tiers).map_err(py_err)
+}
+
+/// Total bits of the packed stream for a buffer of `uint64` values, before byte padding.
+#[pyfunction]
+#[pyo3(signature = (values, config = None))]
+fn encoded_size(
+    py: Python<'_>,
+    values: &Bound<'_, PyAny>,
+    config: Option<&Bound<'_, Config>>,
+) -> PyResult<usize> {
+    let (j_bits, tiers) = resolve(config);
+    values_from_buffer(py, values)?
+        .into_iter()
+        .try_fold(0usize, |total, value| {
+            Ok(total + lotus_encoded_bits(value, j_bits, tiers)?)
+        })
+        .map_err(py_err)
+}
+
+/// Encode a buffer of `uint64` values as one packed bitstream.
+#[pyfunction]
+#[pyo3(signature = (values, config = None))]
+fn encode_array<'py>(
+    py: Python<'py>,
+    values: &Bound<'py, PyAny>,
+    config: Option<&Bound<'py, Config>>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let (j_bits, tiers) = resolve(config);
+    let mut writer = BitWriter::new();
+    for value in values_from_buffer(py, values)? {
+        lotus_write_u64(&mut writer, value, j_bits, tiers).map_err(py_err)?;
+    }
+    Ok(PyBytes::new(py, &writer.into_bytes()))
+}
+
+/// Decode `count` values from a packed bitstream into an `array.array('Q')`.
+#[pyfunction]
+#[pyo3(signature = (data, count, config = None))]
+fn decode_array<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    count: usize,
+    config: Option<&Bound<'py, Config>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let (j_bits, tiers) = resolve(config);
+    let mut reader = BitReader::new(data);
+    let mut raw = Vec::with_capacity(count.min(data.len() * 8) * 8);
+    for _ in 0..count {
+        let value = lotus_read_u64(&mut reader, j_bits, tiers).map_err(py_err)?;
+        raw.extend_from_slice(&value.to_ne_bytes());
+    }
+    py.import("array")?
+        .getattr("array")?
+        .call1(("Q", PyBytes::new(py, &raw)))
+}
+
+#[pymodule]
+#[pyo3(name = "lotus")]
+fn lotus_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Config>()?;
+    module.add("LotusError", module.py().get_type::<LotusError>())?;
+    module.add_function(wrap_pyfunction!(encode, module)?)?;
+    module.add_function(wrap_pyfunction!(decode, module)?)?;
+    module.add_function(wrap_pyfunction!(encoded_bits, module)?)?;
+    module.add_function(wrap_pyfunction!(encoded_size, module)?)?;
+    module.add_function(wrap_pyfunction!(encode_array, module)?)?;
+    module.add_function(wrap_pyfunction!(decode_array, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory holding `liblotus_python.so`: the parent of the `deps` directory this test runs from.
+fn artifact_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    exe.parent().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn python_test_suite() {
+    let library = artifact_dir().join(format!(
+        "{}lotus_python{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ));
+    assert!(library.exists(), "missing {}", library.display());
+    let module_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("python");
+    std::fs::create_dir_all(&module_dir).unwrap();
+    let extension = if cfg!(windows) { "pyd" } else { "so" };
+    std::fs::copy(&library, module_dir.join(format!("lotus.{extension}"))).unwrap();
+
+    let interpreter = std::env::var("PYO3_PYTHON").unwrap_or_else(|_| "python3".to_string());
+    let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/python/test_lotus.py");
+    let output = Command::new(&interpreter)
+        .arg(script)
+        .env("PYTHONPATH", &module_dir)
+        .output()
+        .unwrap_or_else(|err| panic!("failed to run {interpreter}: {err}"));
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,81 @@
+"""Tests for the `lotus` extension module; run by tests/python.rs."""
+
+import array
+import unittest
+
+import lotus
+
+
+class ScalarTests(unittest.TestCase):
+    def test_round_trip(self):
+        for config in (lotus.Config.J2D1, lotus.Config.J3D1, lotus.Config(4, 2)):
+            for value in (0, 1, 2, 300, 65535, 2**28):
+                data = lotus.encode(value, config)
+                decoded, bits = lotus.decode(data, config)
+                self.assertEqual(decoded, value)
+                self.assertEqual(bits, lotus.encoded_bits(value, config))
+                self.assertEqual(len(data), (bits + 7) // 8)
+
+    def test_default_config_covers_u64(self):
+        value = 2**64 - 1
+        self.assertEqual(lotus.decode(lotus.encode(value))[0], value)
+
+    def test_errors(self):
+        with self.assertRaises(lotus.LotusError):
+            lotus.encode(2**64 - 1, lotus.Config(1, 1))
+        with self.assertRaises(ValueError):
+            lotus.decode(b"", lotus.Config.J2D1)
+        with self.assertRaises(OverflowError):
+            lotus.encode(-1)
+        with self.assertRaises(ValueError):
+            lotus.Config(9, 1)
+
+
+class ConfigTests(unittest.TestCase):
+    def test_presets(self):
+        self.assertEqual(lotus.Config.J2D1, lotus.Config(2, 1))
+        self.assertEqual(lotus.Config.J1D2.j_bits, 1)
+        self.assertEqual(lotus.Config.J1D2.tiers, 2)
+        self.assertEqual(repr(lotus.Config.J3D1), "Config(j_bits=3, tiers=1)")
+        self.assertEqual(len({lotus.Config(2, 1), lotus.Config.J2D1}), 1)
+
+
+class BulkTests(unittest.TestCase):
+    def test_array_round_trip(self):
+        values = array.array("Q", [i * i * 7 for i in range(1000)])
+        config = lotus.Config.J2D1
+        data = lotus.encode_array(values, config)
+        bits = lotus.encoded_size(values, config)
+        self.assertEqual(len(data), (bits + 7) // 8)
+        decoded = lotus.decode_array(data, len(values), config)
+        self.assertIsInstance(decoded, array.array)
+        self.assertEqual(decoded.typecode, "Q")
+        self.assertEqual(decoded, values)
+
+    def test_memoryview_input(self):
+        values = array.array("Q", [5, 6, 7])
+        self.assertEqual(lotus.encode_array(memoryview(values)), lotus.encode_array(values))
+
+    def test_rejects_other_element_types(self):
+        with self.assertRaises(TypeError):
+            lotus.encode_array(array.array("i", [1, 2, 3]))
+        with self.assertRaises(TypeError):
+            lotus.encode_array([1, 2, 3])
+
+    def test_truncated_stream(self):
+        data = lotus.encode_array(array.array("Q", range(100)))
+        with self.assertRaises(lotus.LotusError):
+            lotus.decode_array(data[:10], 100)
+
+    def test_numpy(self):
+        try:
+            import numpy
+        except ImportError:
+            self.skipTest("numpy not installed")
+        values = numpy.arange(0, 10_000, 3, dtype=numpy.uint64)
+        decoded = lotus.decode_array(lotus.encode_array(values), len(values))
+        self.assertTrue(numpy.array_equal(numpy.frombuffer(decoded, dtype=numpy.uint64), values))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,216 @@
+//! Dataset analysis: value distribution and the exact cost of coding it under each scheme.
+//!
+//! Baseline codes map `v` to `v + 1` where they cannot represent zero (Elias gamma and delta),
+//! the usual convention for coding non-negative integers with them.
+
+use std::collections::HashMap;
+
+use crate::universal::{elias_delta_bits, elias_gamma_bits};
+use crate::{leb128_len, lotus_encoded_bits};
+
+/// Largest jumpstarter width considered by [`Analysis::lotus`].
+pub const ANALYSIS_MAX_J_BITS: usize = 8;
+/// Largest tier count considered by [`Analysis::lotus`].
+pub const ANALYSIS_MAX_TIERS: usize = 4;
+
+/// Streaming accumulator for [`Analysis`]; memory grows with the number of distinct values.
+#[derive(Debug, Default, Clone)]
+pub struct Analyzer {
+    counts: HashMap<u64, u64>,
+    count: u64,
+}
+
+impl Analyzer {
+    /// An empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one value.
+    pub fn push(&mut self, value: u64) {
+        *self.counts.entry(value).or_insert(0) += 1;
+        self.count += 1;
+    }
+
+    /// Compute every statistic and coding cost for the values pushed so far.
+    pub fn finish(self) -> Analysis {
+        let mut histogram = [0u64; 65];
+        let mut entropy = 0.0;
+        let mut leb128_bits = 0u64;
+        let mut gamma_bits = 0u64;
+        let mut delta_bits = 0u64;
+        for (&value, &freq) in &self.counts {
+            histogram[bit_length(value)] += freq;
+            let p = freq as f64 / self.count as f64;
+            entropy -= p * p.log2();
+            leb128_bits += freq * leb128_len(value) as u64 * 8;
+            gamma_bits += freq * elias_gamma_bits(value) as u64;
+            delta_bits += freq * elias_delta_bits(value) as u64;
+        }
+        let min = self.counts.keys().copied().min();
+        let max = self.counts.keys().copied().max();
+        let fixed_width = bit_length(max.unwrap_or(0)).max(1);
+
+        let mut lotus = Vec::with_capacity(ANALYSIS_MAX_J_BITS * ANALYSIS_MAX_TIERS);
+        for j_bits in 1..=ANALYSIS_MAX_J_BITS {
+            for tiers in 1..=ANALYSIS_MAX_TIERS {
+                let total_bits = self.counts.iter().try_fold(0u64, |total, (&value, &freq)| {
+                    let bits = lotus_encoded_bits(value, j_bits, tiers).ok()?;
+                    Some(total + freq * bits as u64)
+                });
+                lotus.push(LotusCost {
+                    j_bits,
+                    tiers,
+                    total_bits,
+                });
+            }
+        }
+
+        Analysis {
+            count: self.count,
+            distinct: self.counts.len() as u64,
+            min,
+            max,
+            entropy: entropy.max(0.0),
+            histogram,
+            lotus,
+            leb128_bits,
+            elias_gamma_bits: gamma_bits,
+            elias_delta_bits: delta_bits,
+            fixed_width,
+            fixed_width_bits: fixed_width as u64 * self.count,
+        }
+    }
+}
+
+/// Exact cost of a dataset under one Lotus configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LotusCost {
+    pub j_bits: usize,
+    pub tiers: usize,
+    /// Total codeword bits, or `None` if some value exceeds the configuration's range.
+    pub total_bits: Option<u64>,
+}
+
+/// Distribution statistics and coding costs for a dataset.
+///
+/// Bit totals are exact packed sizes without byte padding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub count: u64,
+    pub distinct: u64,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    /// Order-0 Shannon entropy of the values in bits/value: a lower bound for any code that
+    /// assigns each value a fixed codeword.
+    pub entropy: f64,
+    /// Value counts by bit length: index 0 counts zeros, index `k` values in `2^(k-1)..2^k`.
+    pub histogram: [u64; 65],
+    /// Every configuration with `J` in `1..=ANALYSIS_MAX_J_BITS` and `d` in
+    /// `1..=ANALYSIS_MAX_TIERS`, ordered by `J` then `d`.
+    pub lotus: Vec<LotusCost>,
+    pub leb128_bits: u64,
+    pub elias_gamma_bits: u64,
+    pub elias_delta_bits: u64,
+    /// Bits per value of a fixed-width code sized to the maximum.
+    pub fixed_width: usize,
+    pub fixed_width_bits: u64,
+}
+
+impl Analysis {
+    /// Analyze a complete dataset.
+    pub fn from_values(values: impl IntoIterator<Item = u64>) -> Self {
+        let mut analyzer = Analyzer::new();
+        values.into_iter().for_each(|value| analyzer.push(value));
+        analyzer.finish()
+    }
+
+    /// The cheapest Lotus configuration; ties go to the smaller `J`, then the fewer tiers.
+    /// `None` only for an empty dataset.
+    pub fn recommended(&self) -> Option<LotusCost> {
+        if self.count == 0 {
+            return None;
+        }
+        self.lotus
+            .iter()
+            .filter(|cost| cost.total_bits.is_some())
+            .min_by_key(|cost| cost.total_bits)
+            .copied()
+    }
+
+    /// `total_bits` spread over the dataset; zero for an empty dataset.
+    pub fn bits_per_value(&self, total_bits: u64) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            total_bits as f64 / self.count as f64
+        }
+    }
+}
+
+fn bit_length(value: u64) -> usize {
+    64 - value.leading_zeros() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LOTUS_J2D1;
+
+    #[test]
+    fn baseline_lengths() {
+        // gamma(1) = "1", gamma(2) = "010", delta(1) = "1", delta(2) = "0100".
+        assert_eq!(elias_gamma_bits(0), 1);
+        assert_eq!(elias_gamma_bits(1), 3);
+        assert_eq!(elias_delta_bits(0), 1);
+        assert_eq!(elias_delta_bits(1), 4);
+        assert_eq!(elias_gamma_bits(u64::MAX), 129);
+        assert_eq!(elias_delta_bits(u64::MAX), 64 + elias_gamma_bits(64));
+    }
+
+    #[test]
+    fn statistics_and_costs() {
+        let values = [0u64, 1, 1, 3, 3, 3, 3, 1_000];
+        let analysis = Analysis::from_values(values);
+        assert_eq!(analysis.count, 8);
+        assert_eq!(analysis.distinct, 4);
+        assert_eq!((analysis.min, analysis.max), (Some(0), Some(1_000)));
+        // Probabilities 1/8, 1/4, 1/2, 1/8.
+        assert!((analysis.entropy - 1.75).abs() < 1e-12);
+        assert_eq!(analysis.histogram[0], 1);
+        assert_eq!(analysis.histogram[1], 2);
+        assert_eq!(analysis.histogram[2], 4);
+        assert_eq!(analysis.histogram[10], 1);
+        assert_eq!(analysis.fixed_width_bits, 80);
+        assert_eq!(analysis.leb128_bits, 7 * 8 + 16);
+
+        let j2d1 = analysis
+            .lotus
+            .iter()
+            .find(|cost| (cost.j_bits, cost.tiers) == LOTUS_J2D1)
+            .unwrap();
+        let expected: usize = values
+            .iter()
+            .map(|&v| lotus_encoded_bits(v, 2, 1).unwrap())
+            .sum();
+        assert_eq!(j2d1.total_bits, Some(expected as u64));
+        let best = analysis.recommended().unwrap();
+        assert!(analysis.lotus.iter().all(|cost| {
+            cost.total_bits
+                .is_none_or(|bits| bits >= best.total_bits.unwrap())
+        }));
+    }
+
+    #[test]
+    fn out_of_range_configs_and_empty_input() {
+        let analysis = Analysis::from_values([u64::MAX]);
+        let j1d1 = analysis.lotus[0];
+        assert_eq!((j1d1.j_bits, j1d1.tiers, j1d1.total_bits), (1, 1, None));
+        assert!(analysis.recommended().is_some());
+
+        let empty = Analysis::from_values([]);
+        assert_eq!(empty.recommended(), None);
+        assert_eq!(empty.entropy, 0.0);
+        assert_eq!(empty.bits_per_value(0), 0.0);
+    }
+}
@@ -0,0 +1,259 @@
+//! Lotus encoding of Apache Arrow integer arrays.
+//!
+//! Layout, with metadata in [`LOTUS_J3D1`](crate::LOTUS_J3D1): data type tag, length, null
+//! count, the validity bitmap as raw bits (only when the null count is non-zero), the sequence
+//! codec tag, and finally the non-null values under that codec in the caller's `(j_bits, tiers)`.
+//! Null slots contribute nothing to the value stream and decode as nulls over zero values.
+//!
+//! `Int64` values are mapped to `u64` before the sequence codec: zigzag for
+//! [`SequenceCodec::Plain`] and [`SequenceCodec::Rle`], two's complement for
+//! [`SequenceCodec::Delta`] (whose differences are already zigzagged) and offset binary for
+//! [`SequenceCodec::FrameOfReference`], so the block minimum is the smallest signed value.
+
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Int64Type, UInt32Type, UInt64Type};
+use arrow_array::{Array, ArrayRef, PrimitiveArray};
+use arrow_buffer::NullBuffer;
+use arrow_schema::DataType;
+
+use crate::{
+    BitReader, BitWriter, HEADER_CONFIG, LotusError, SequenceCodec, lotus_read_u64,
+    lotus_write_u64, sequence_read, sequence_write, zigzag_decode, zigzag_encode,
+};
+
+const TYPE_UINT64: u64 = 0;
+const TYPE_INT64: u64 = 1;
+const TYPE_UINT32: u64 = 2;
+
+const SIGN_BIT: u64 = 1 << 63;
+
+fn signed_to_unsigned(value: i64, codec: SequenceCodec) -> u64 {
+    match codec {
+        SequenceCodec::Plain | SequenceCodec::Rle => zigzag_encode(value),
+        SequenceCodec::Delta => value as u64,
+        SequenceCodec::FrameOfReference => value as u64 ^ SIGN_BIT,
+    }
+}
+
+fn unsigned_to_signed(value: u64, codec: SequenceCodec) -> i64 {
+    match codec {
+        SequenceCodec::Plain | SequenceCodec::Rle => zigzag_decode(value),
+        SequenceCodec::Delta => value as i64,
+        SequenceCodec::FrameOfReference => (value ^ SIGN_BIT) as i64,
+    }
+}
+
+/// Non-null values of a primitive array, in order.
+fn present<T, U>(array: &PrimitiveArray<T>, map: impl Fn(T::Native) -> U) -> Vec<U>
+where
+    T: arrow_array::ArrowPrimitiveType,
+{
+    array.iter().flatten().map(map).collect()
+}
+
+/// Encode a `UInt64`, `Int64` or `UInt32` array, skipping null slots in the value stream.
+pub fn encode_array(
+    array: &dyn Array,
+    codec: SequenceCodec,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u8>, LotusError> {
+    let (type_tag, values) = match array.data_type() {
+        DataType::UInt64 => (
+            TYPE_UINT64,
+            present(array.as_primitive::<UInt64Type>(), |v| v),
+        ),
+        DataType::Int64 => (
+            TYPE_INT64,
+            present(array.as_primitive::<Int64Type>(), |v| {
+                signed_to_unsigned(v, codec)
+            }),
+        ),
+        DataType::UInt32 => (
+            TYPE_UINT32,
+            present(array.as_primitive::<UInt32Type>(), u64::from),
+        ),
+        other => {
+            return Err(LotusError::Message(format!(
+                "unsupported Arrow data type {other}"
+            )));
+        }
+    };
+    let (hj, hd) = HEADER_CONFIG;
+    let mut writer = BitWriter::new();
+    lotus_write_u64(&mut writer, type_tag, hj, hd)?;
+    lotus_write_u64(&mut writer, array.len() as u64, hj, hd)?;
+    lotus_write_u64(&mut writer, array.null_count() as u64, hj, hd)?;
+    if let Some(nulls) = array.nulls().filter(|nulls| nulls.null_count() > 0) {
+        for valid in nulls.iter() {
+            writer.write_bits(valid as u64, 1)?;
+        }
+    }
+    lotus_write_u64(&mut writer, codec.tag(), hj, hd)?;
+    sequence_write(&mut writer, &values, codec, j_bits, tiers)?;
+    Ok(writer.into_bytes())
+}
+
+/// Spread `values` over the valid slots of a `len`-slot array, leaving zeros under nulls.
+fn scatter<T: Copy + Default>(values: Vec<T>, nulls: Option<&NullBuffer>, len: usize) -> Vec<T> {
+    match nulls {
+        None => values,
+        Some(nulls) => {
+            let mut values = values.into_iter();
+            let mut out = vec![T::default(); len];
+            for index in nulls.valid_indices() {
+                // The caller read exactly one value per valid slot.
+                out[index] = values.next().unwrap_or_default();
+            }
+            out
+        }
+    }
+}
+
+/// Decode an array produced by [`encode_array`] with the same `(j_bits, tiers)`.
+pub fn decode_array(bytes: &[u8], j_bits: usize, tiers: usize) -> Result<ArrayRef, LotusError> {
+    let (hj, hd) = HEADER_CONFIG;
+    let mut reader = BitReader::new(bytes);
+    let type_tag = lotus_read_u64(&mut reader, hj, hd)?;
+    let len = lotus_read_u64(&mut reader, hj, hd)?;
+    let null_count = lotus_read_u64(&mut reader, hj, hd)?;
+    if null_count > len {
+        return Err(LotusError::InvalidEncoding);
+    }
+    // The bitmap takes one bit per slot and every value at least one more.
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|&len| len <= reader.bits_remaining())
+        .ok_or(LotusError::UnexpectedEof)?;
+    let null_count = null_count as usize;
+    let nulls = if null_count > 0 {
+        let mut valid = Vec::with_capacity(len);
+        for _ in 0..len {
+            valid.push(reader.read_bits(1)? == 1);
+        }
+        let nulls = NullBuffer::from(valid);
+        if nulls.null_count() != null_count {
+            return Err(LotusError::InvalidEncoding);
+        }
+        Some(nulls)
+    } else {
+        None
+    };
+    let codec = SequenceCodec::from_tag(lotus_read_u64(&mut reader, hj, hd)?)?;
+    let values = sequence_read(&mut reader, len - null_count, codec, j_bits, tiers)?;
+    let array: ArrayRef = match type_tag {
+        TYPE_UINT64 => Arc::new(PrimitiveArray::<UInt64Type>::new(
+            scatter(values, nulls.as_ref(), len).into(),
+            nulls,
+        )),
+        TYPE_INT64 => {
+            let values = values
+                .into_iter()
+                .map(|value| unsigned_to_signed(value, codec))
+                .collect();
+            Arc::new(PrimitiveArray::<Int64Type>::new(
+                scatter(values, nulls.as_ref(), len).into(),
+                nulls,
+            ))
+        }
+        TYPE_UINT32 => {
+            let values = values
+                .into_iter()
+                .map(|value| u32::try_from(value).map_err(|_| LotusError::InvalidEncoding))
+                .collect::<Result<Vec<_>, _>>()?;
+            Arc::new(PrimitiveArray::<UInt32Type>::new(
+                scatter(values, nulls.as_ref(), len).into(),
+                nulls,
+            ))
+        }
+        _ => return Err(LotusError::InvalidEncoding),
+    };
+    Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1};
+    use arrow_array::{Int64Array, StringArray, UInt32Array, UInt64Array};
+
+    const CODECS: [SequenceCodec; 4] = [
+        SequenceCodec::Plain,
+        SequenceCodec::Delta,
+        SequenceCodec::FrameOfReference,
+        SequenceCodec::Rle,
+    ];
+
+    fn round_trip(array: &dyn Array, cfg: (usize, usize)) {
+        for codec in CODECS {
+            let bytes = encode_array(array, codec, cfg.0, cfg.1).unwrap();
+            let decoded = decode_array(&bytes, cfg.0, cfg.1).unwrap();
+            assert_eq!(decoded.to_data(), array.to_data(), "{codec:?}");
+        }
+    }
+
+    #[test]
+    fn all_types_round_trip_with_nulls() {
+        round_trip(
+            &UInt64Array::from(vec![Some(3), None, Some(u64::MAX), Some(0), None]),
+            LOTUS_J3D1,
+        );
+        round_trip(
+            &Int64Array::from(vec![
+                None,
+                Some(-5),
+                Some(i64::MIN),
+                Some(i64::MAX),
+                Some(0),
+            ]),
+            LOTUS_J3D1,
+        );
+        round_trip(
+            &UInt32Array::from(vec![Some(1), Some(2), None, Some(u32::MAX)]),
+            LOTUS_J3D1,
+        );
+        round_trip(&UInt64Array::from(vec![1u64, 2, 3]), LOTUS_J2D1);
+        round_trip(&Int64Array::from(Vec::<i64>::new()), LOTUS_J2D1);
+        round_trip(&UInt32Array::from(vec![None, None]), LOTUS_J2D1);
+    }
+
+    #[test]
+    fn sliced_arrays() {
+        let array = Int64Array::from(vec![Some(9), None, Some(-1), Some(4), None, Some(2)]);
+        round_trip(&array.slice(1, 4), LOTUS_J2D1);
+    }
+
+    #[test]
+    fn nulls_cost_only_their_bitmap_bit() {
+        let (j_bits, tiers) = LOTUS_J2D1;
+        let dense = UInt64Array::from(vec![1_000u64; 64]);
+        let sparse = UInt64Array::from(
+            (0..128)
+                .map(|i| (i % 2 == 0).then_some(1_000u64))
+                .collect::<Vec<_>>(),
+        );
+        let dense = encode_array(&dense, SequenceCodec::Plain, j_bits, tiers).unwrap();
+        let sparse = encode_array(&sparse, SequenceCodec::Plain, j_bits, tiers).unwrap();
+        // 128 bitmap bits plus slightly larger length and null-count fields.
+        assert!(sparse.len() <= dense.len() + 16 + 2);
+    }
+
+    #[test]
+    fn unsupported_and_corrupt_input() {
+        let strings = StringArray::from(vec!["a"]);
+        assert!(matches!(
+            encode_array(&strings, SequenceCodec::Plain, 3, 1),
+            Err(LotusError::Message(_))
+        ));
+        let bytes = encode_array(
+            &UInt32Array::from(vec![7u32; 20]),
+            SequenceCodec::Plain,
+            3,
+            1,
+        )
+        .unwrap();
+        assert!(decode_array(&bytes[..bytes.len() / 2], 3, 1).is_err());
+    }
+}
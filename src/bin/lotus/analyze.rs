@@ -0,0 +1,169 @@
+//! `analyze`: distribution statistics and per-scheme coding costs for an integer dataset.
+
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use lotus::{Analysis, Analyzer};
+
+use crate::common::{CliError, Config, open_input};
+use crate::formats::{ValueInput, read_values};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Args)]
+pub struct AnalyzeArgs {
+    /// Integers to analyze; stdin when omitted or `-`
+    input: Option<PathBuf>,
+    #[command(flatten)]
+    values: ValueInput,
+    /// Report layout
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+/// Rows of the cost comparison: each scheme's name and total bits, if it can code the data.
+fn schemes(analysis: &Analysis) -> Vec<(String, Option<u64>)> {
+    let mut rows: Vec<_> = analysis
+        .lotus
+        .iter()
+        .map(|cost| {
+            let config = Config {
+                j_bits: cost.j_bits,
+                tiers: cost.tiers,
+            };
+            (config.to_string(), cost.total_bits)
+        })
+        .collect();
+    rows.push(("leb128".to_string(), Some(analysis.leb128_bits)));
+    rows.push(("elias-gamma".to_string(), Some(analysis.elias_gamma_bits)));
+    rows.push(("elias-delta".to_string(), Some(analysis.elias_delta_bits)));
+    rows.push((
+        format!("fixed-{}", analysis.fixed_width),
+        Some(analysis.fixed_width_bits),
+    ));
+    rows
+}
+
+fn recommended(analysis: &Analysis) -> Option<(Config, u64)> {
+    let cost = analysis.recommended()?;
+    let config = Config {
+        j_bits: cost.j_bits,
+        tiers: cost.tiers,
+    };
+    Some((config, cost.total_bits?))
+}
+
+fn render_table(analysis: &Analysis) -> String {
+    let mut out = String::new();
+    let range = match (analysis.min, analysis.max) {
+        (Some(min), Some(max)) => format!(", min {min}, max {max}"),
+        _ => String::new(),
+    };
+    let _ = writeln!(
+        out,
+        "values: {} ({} distinct{range})",
+        analysis.count, analysis.distinct
+    );
+    let _ = writeln!(out, "entropy: {:.4} bits/value", analysis.entropy);
+
+    let _ = writeln!(out, "\n{:>10}  {:>12}", "bit length", "count");
+    let peak = analysis.histogram.iter().copied().max().unwrap_or(0).max(1);
+    for (bits, &count) in analysis.histogram.iter().enumerate() {
+        if count > 0 {
+            let bar = "#".repeat((count * 40).div_ceil(peak) as usize);
+            let _ = writeln!(out, "{bits:>10}  {count:>12}  {bar}");
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "\n{:<12}  {:>10}  {:>14}",
+        "scheme", "bits/value", "total bits"
+    );
+    for (name, total_bits) in schemes(analysis) {
+        match total_bits {
+            Some(bits) => {
+                let _ = writeln!(
+                    out,
+                    "{name:<12}  {:>10.4}  {bits:>14}",
+                    analysis.bits_per_value(bits)
+                );
+            }
+            None => {
+                let _ = writeln!(out, "{name:<12}  {:>10}  {:>14}", "n/a", "n/a");
+            }
+        }
+    }
+    match recommended(analysis) {
+        Some((config, bits)) => {
+            let _ = writeln!(
+                out,
+                "\nrecommended: {config} ({:.4} bits/value)",
+                analysis.bits_per_value(bits)
+            );
+        }
+        None => out.push_str("\nrecommended: none (no values)\n"),
+    }
+    out
+}
+
+fn json_option(value: Option<u64>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
+
+fn render_json(analysis: &Analysis) -> String {
+    let histogram: Vec<String> = analysis
+        .histogram
+        .iter()
+        .enumerate()
+        .filter(|&(_, &count)| count > 0)
+        .map(|(bits, count)| format!("{{\"bit_length\":{bits},\"count\":{count}}}"))
+        .collect();
+    let schemes: Vec<String> = schemes(analysis)
+        .into_iter()
+        .map(|(name, total_bits)| {
+            let per_value = total_bits.map_or_else(
+                || "null".to_string(),
+                |bits| format!("{:.4}", analysis.bits_per_value(bits)),
+            );
+            format!(
+                "{{\"name\":\"{name}\",\"total_bits\":{},\"bits_per_value\":{per_value}}}",
+                json_option(total_bits)
+            )
+        })
+        .collect();
+    let recommended = recommended(analysis)
+        .map_or_else(|| "null".to_string(), |(config, _)| format!("\"{config}\""));
+    format!(
+        "{{\"count\":{},\"distinct\":{},\"min\":{},\"max\":{},\"entropy\":{:.4},\
+         \"histogram\":[{}],\"schemes\":[{}],\"recommended\":{recommended}}}\n",
+        analysis.count,
+        analysis.distinct,
+        json_option(analysis.min),
+        json_option(analysis.max),
+        analysis.entropy,
+        histogram.join(","),
+        schemes.join(","),
+    )
+}
+
+pub fn analyze(args: AnalyzeArgs) -> Result<(), CliError> {
+    let mut analyzer = Analyzer::new();
+    read_values(open_input(args.input.as_deref())?, &args.values, |value| {
+        analyzer.push(value);
+        Ok(())
+    })?;
+    let analysis = analyzer.finish();
+    let report = match args.format {
+        Format::Table => render_table(&analysis),
+        Format::Json => render_json(&analysis),
+    };
+    std::io::stdout().lock().write_all(report.as_bytes())?;
+    Ok(())
+}
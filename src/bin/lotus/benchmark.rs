@@ -0,0 +1,458 @@
+//! `benchmark`: exact sizes and encode/decode throughput over real or synthetic workloads.
+
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use clap::{Args, ValueEnum};
+use lotus::{
+    Analysis, BitReader, BitWriter, EliasDelta, EliasGamma, EliasOmega, ExpGolomb, Fibonacci,
+    GolombRice, IntegerCodec, Leb128, LotusCodec, LotusError,
+};
+
+use crate::common::{CliError, Config, open_input};
+use crate::formats::{ValueInput, read_values};
+
+/// A synthetic value distribution.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Distribution {
+    /// Uniform over `0..2^bits`.
+    Uniform { bits: u32 },
+    /// Number of failures before the first success, success probability `p`.
+    Geometric { p: f64 },
+    /// Ranks `0..n` with probability proportional to `1 / (rank + 1)^s`.
+    Zipf { n: u64, s: f64 },
+    /// Bit length uniform over `0..=bits`, then uniform within that length.
+    LogUniform { bits: u32 },
+}
+
+impl std::str::FromStr for Distribution {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!(
+                "expected uniform:BITS, geometric:P, zipf:N:S or log-uniform:BITS, got `{spec}`"
+            )
+        };
+        let parts: Vec<&str> = spec.split(':').collect();
+        let bits = |text: &str| {
+            text.parse::<u32>()
+                .ok()
+                .filter(|bits| (1..=64).contains(bits))
+                .ok_or_else(err)
+        };
+        match parts.as_slice() {
+            ["uniform", b] => Ok(Self::Uniform { bits: bits(b)? }),
+            ["log-uniform", b] => Ok(Self::LogUniform { bits: bits(b)? }),
+            ["geometric", p] => {
+                let p: f64 = p.parse().map_err(|_| err())?;
+                if p > 0.0 && p < 1.0 {
+                    Ok(Self::Geometric { p })
+                } else {
+                    Err(err())
+                }
+            }
+            ["zipf", n, s] => {
+                let n: u64 = n.parse().map_err(|_| err())?;
+                let s: f64 = s.parse().map_err(|_| err())?;
+                if (1..=1 << 24).contains(&n) && s > 0.0 {
+                    Ok(Self::Zipf { n, s })
+                } else {
+                    Err(err())
+                }
+            }
+            _ => Err(err()),
+        }
+    }
+}
+
+impl std::fmt::Display for Distribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Uniform { bits } => write!(f, "uniform:{bits}"),
+            Self::Geometric { p } => write!(f, "geometric:{p}"),
+            Self::Zipf { n, s } => write!(f, "zipf:{n}:{s}"),
+            Self::LogUniform { bits } => write!(f, "log-uniform:{bits}"),
+        }
+    }
+}
+
+/// SplitMix64: small, fast and reproducible, which is all a workload generator needs.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform over `0..2^bits`, for `bits` in `0..=64`.
+    fn bits(&mut self, bits: u32) -> u64 {
+        match bits {
+            0 => 0,
+            _ => self.next_u64() >> (64 - bits),
+        }
+    }
+}
+
+impl Distribution {
+    fn sample(&self, count: usize, seed: u64) -> Vec<u64> {
+        let mut rng = SplitMix64(seed);
+        match *self {
+            Self::Uniform { bits } => (0..count).map(|_| rng.bits(bits)).collect(),
+            Self::Geometric { p } => {
+                let scale = (1.0 - p).ln();
+                (0..count)
+                    .map(|_| ((1.0 - rng.next_f64()).ln() / scale) as u64)
+                    .collect()
+            }
+            Self::Zipf { n, s } => {
+                let mut cdf = Vec::with_capacity(n as usize);
+                let mut total = 0.0;
+                for rank in 0..n {
+                    total += 1.0 / ((rank + 1) as f64).powf(s);
+                    cdf.push(total);
+                }
+                (0..count)
+                    .map(|_| {
+                        let target = rng.next_f64() * total;
+                        cdf.partition_point(|&c| c <= target).min(n as usize - 1) as u64
+                    })
+                    .collect()
+            }
+            Self::LogUniform { bits } => (0..count)
+                .map(|_| {
+                    let len = (rng.next_u64() % (u64::from(bits) + 1)) as u32;
+                    match len {
+                        0 => 0,
+                        _ => (1u64 << (len - 1)) | rng.bits(len - 1),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Args)]
+pub struct BenchmarkArgs {
+    /// Datasets to benchmark, read like `pack` input
+    #[arg(long = "input", value_name = "FILE")]
+    inputs: Vec<PathBuf>,
+    #[command(flatten)]
+    values: ValueInput,
+    /// Synthetic workloads: uniform:BITS, geometric:P, zipf:N:S or log-uniform:BITS
+    #[arg(long = "dist", value_name = "SPEC")]
+    distributions: Vec<Distribution>,
+    /// Values generated per synthetic workload
+    #[arg(long, default_value_t = 100_000)]
+    count: usize,
+    /// Seed for synthetic workloads
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+    /// Lotus configurations to compare
+    #[arg(
+        short,
+        long = "config",
+        value_delimiter = ',',
+        default_value = "J2D1,J3D1"
+    )]
+    configs: Vec<Config>,
+    /// Timed runs per measurement; the fastest is reported
+    #[arg(long, default_value_t = 5)]
+    repeat: u32,
+    /// Report layout
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+const DEFAULT_DISTRIBUTIONS: [&str; 5] = [
+    "uniform:8",
+    "uniform:32",
+    "geometric:0.05",
+    "zipf:10000:1.1",
+    "log-uniform:64",
+];
+
+/// Fastest of `repeat` runs of `run`.
+fn best_time(repeat: u32, mut run: impl FnMut()) -> Duration {
+    (0..repeat.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// One codec on one workload. Timings are `None` for size-only baselines.
+struct Row {
+    workload: String,
+    codec: String,
+    count: usize,
+    total_bits: Option<u64>,
+    encode: Option<Duration>,
+    decode: Option<Duration>,
+}
+
+impl Row {
+    fn bits_per_value(&self) -> Option<f64> {
+        self.total_bits
+            .map(|bits| bits as f64 / self.count.max(1) as f64)
+    }
+
+    fn values_per_sec(&self, time: Option<Duration>) -> Option<f64> {
+        time.map(|time| self.count as f64 / time.as_secs_f64().max(f64::MIN_POSITIVE))
+    }
+
+    /// Throughput in MB/s of the uncompressed input, eight bytes per value.
+    fn mb_per_sec(&self, time: Option<Duration>) -> Option<f64> {
+        self.values_per_sec(time).map(|rate| rate * 8.0 / 1e6)
+    }
+}
+
+/// Round-trip `values` through `codec`, then time it. Values the codec cannot represent leave the
+/// row without a size or timings.
+fn codec_row(
+    workload: &str,
+    values: &[u64],
+    codec: &dyn IntegerCodec,
+    repeat: u32,
+) -> Result<Row, LotusError> {
+    let mut row = Row {
+        workload: workload.to_string(),
+        codec: codec.name(),
+        count: values.len(),
+        total_bits: None,
+        encode: None,
+        decode: None,
+    };
+    let mut encoded = BitWriter::new();
+    for &value in values {
+        match codec.encode(&mut encoded, value) {
+            Ok(()) => {}
+            Err(LotusError::ValueTooLarge) => return Ok(row),
+            Err(err) => return Err(err),
+        }
+    }
+    row.total_bits = Some(encoded.bits_written() as u64);
+    let bytes = encoded.clone().into_bytes();
+    let mut reader = BitReader::new(&bytes);
+    for &value in values {
+        if codec.decode(&mut reader)? != value {
+            return Err(LotusError::Message(format!(
+                "{} round trip failed on {workload}",
+                row.codec
+            )));
+        }
+    }
+
+    // The writer already holds the whole output, so timed runs reuse its buffer and never grow it.
+    row.encode = Some(best_time(repeat, || {
+        encoded.clear();
+        for &value in values {
+            let _ = codec.encode(&mut encoded, black_box(value));
+        }
+        black_box(encoded.bits_written());
+    }));
+    row.decode = Some(best_time(repeat, || {
+        let mut reader = BitReader::new(black_box(&bytes));
+        for _ in 0..values.len() {
+            black_box(codec.decode(&mut reader).ok());
+        }
+    }));
+    Ok(row)
+}
+
+/// Parameter for the Golomb-style codes: the bit length of the mean, less one.
+fn golomb_parameter(values: &[u64]) -> u32 {
+    let mean = values.iter().map(|&value| value as f64).sum::<f64>() / values.len().max(1) as f64;
+    if mean < 2.0 {
+        0
+    } else {
+        mean.log2().floor() as u32
+    }
+}
+
+fn workload_rows(
+    workload: &str,
+    values: &[u64],
+    configs: &[Config],
+    repeat: u32,
+) -> Result<Vec<Row>, LotusError> {
+    let analysis = Analysis::from_values(values.iter().copied());
+    let mut rows = Vec::new();
+    for config in configs {
+        let codec = LotusCodec::new(config.j_bits, config.tiers);
+        rows.push(codec_row(workload, values, &codec, repeat)?);
+    }
+    let k = golomb_parameter(values);
+    let baselines: [&dyn IntegerCodec; 7] = [
+        &Leb128,
+        &EliasGamma,
+        &EliasDelta,
+        &EliasOmega,
+        &Fibonacci,
+        &ExpGolomb { k },
+        &GolombRice { k },
+    ];
+    for codec in baselines {
+        rows.push(codec_row(workload, values, codec, repeat)?);
+    }
+    // Fixed width needs no codec to be sized.
+    rows.push(Row {
+        workload: workload.to_string(),
+        codec: format!("fixed-{}", analysis.fixed_width),
+        count: values.len(),
+        total_bits: Some(analysis.fixed_width_bits),
+        encode: None,
+        decode: None,
+    });
+    Ok(rows)
+}
+
+fn cell(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(String::new, |value| format!("{value:.precision$}"))
+}
+
+fn json_number(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(
+        || "null".to_string(),
+        |value| format!("{value:.precision$}"),
+    )
+}
+
+/// Quote a CSV field, doubling any quotes inside it; workloads named after files may hold commas.
+fn csv_quoted(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+fn render(rows: &[Row], format: Format) -> String {
+    let mut out = String::new();
+    match format {
+        Format::Table => {
+            out.push_str(&format!(
+                "{:<18} {:<14} {:>10} {:>14} {:>12} {:>10} {:>12} {:>10}\n",
+                "workload",
+                "codec",
+                "bits/value",
+                "total bits",
+                "enc Mval/s",
+                "enc MB/s",
+                "dec Mval/s",
+                "dec MB/s"
+            ));
+            for row in rows {
+                let mvals = |time| row.values_per_sec(time).map(|rate| rate / 1e6);
+                let line = format!(
+                    "{:<18} {:<14} {:>10} {:>14} {:>12} {:>10} {:>12} {:>10}",
+                    row.workload,
+                    row.codec,
+                    row.bits_per_value()
+                        .map_or_else(|| "n/a".to_string(), |bits| format!("{bits:.3}")),
+                    row.total_bits
+                        .map_or_else(|| "n/a".to_string(), |bits| bits.to_string()),
+                    cell(mvals(row.encode), 2),
+                    cell(row.mb_per_sec(row.encode), 1),
+                    cell(mvals(row.decode), 2),
+                    cell(row.mb_per_sec(row.decode), 1),
+                );
+                // Size-only rows leave the timing columns blank.
+                out.push_str(line.trim_end());
+                out.push('\n');
+            }
+        }
+        Format::Csv => {
+            out.push_str(
+                "workload,codec,count,total_bits,bits_per_value,\
+                 encode_values_per_sec,encode_mb_per_sec,decode_values_per_sec,decode_mb_per_sec\n",
+            );
+            for row in rows {
+                out.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{}\n",
+                    csv_quoted(&row.workload),
+                    row.codec,
+                    row.count,
+                    row.total_bits
+                        .map_or_else(String::new, |bits| bits.to_string()),
+                    cell(row.bits_per_value(), 4),
+                    cell(row.values_per_sec(row.encode), 0),
+                    cell(row.mb_per_sec(row.encode), 2),
+                    cell(row.values_per_sec(row.decode), 0),
+                    cell(row.mb_per_sec(row.decode), 2),
+                ));
+            }
+        }
+        Format::Json => {
+            let items: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    format!(
+                        "{{\"workload\":\"{}\",\"codec\":\"{}\",\"count\":{},\"total_bits\":{},\
+                         \"bits_per_value\":{},\"encode_values_per_sec\":{},\
+                         \"encode_mb_per_sec\":{},\"decode_values_per_sec\":{},\
+                         \"decode_mb_per_sec\":{}}}",
+                        row.workload.replace('\\', "\\\\").replace('"', "\\\""),
+                        row.codec,
+                        row.count,
+                        row.total_bits
+                            .map_or_else(|| "null".to_string(), |bits| bits.to_string()),
+                        json_number(row.bits_per_value(), 4),
+                        json_number(row.values_per_sec(row.encode), 0),
+                        json_number(row.mb_per_sec(row.encode), 2),
+                        json_number(row.values_per_sec(row.decode), 0),
+                        json_number(row.mb_per_sec(row.decode), 2),
+                    )
+                })
+                .collect();
+            out.push_str(&format!("[{}]\n", items.join(",")));
+        }
+    }
+    out
+}
+
+pub fn benchmark(args: BenchmarkArgs) -> Result<(), CliError> {
+    let mut workloads: Vec<(String, Vec<u64>)> = Vec::new();
+    for path in &args.inputs {
+        let mut values = Vec::new();
+        read_values(open_input(Some(path))?, &args.values, |value| {
+            values.push(value);
+            Ok(())
+        })?;
+        workloads.push((path.display().to_string(), values));
+    }
+    let mut distributions = args.distributions;
+    if distributions.is_empty() && workloads.is_empty() {
+        distributions = DEFAULT_DISTRIBUTIONS
+            .iter()
+            .map(|spec| spec.parse().expect("default distributions parse"))
+            .collect();
+    }
+    for distribution in distributions {
+        let values = distribution.sample(args.count, args.seed);
+        workloads.push((distribution.to_string(), values));
+    }
+
+    let mut rows = Vec::new();
+    for (name, values) in &workloads {
+        rows.extend(workload_rows(name, values, &args.configs, args.repeat)?);
+    }
+    print!("{}", render(&rows, args.format));
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Pieces shared by the subcommands: configuration parsing, input/output plumbing and errors.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use lotus::LotusError;
+
+/// A `(J, d)` configuration written as `J2D1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub j_bits: usize,
+    pub tiers: usize,
+}
+
+impl std::str::FromStr for Config {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("expected a configuration like J2D1, got `{s}`");
+        let upper = s.to_ascii_uppercase();
+        let (j_bits, tiers) = upper
+            .strip_prefix('J')
+            .and_then(|rest| rest.split_once('D'))
+            .ok_or_else(err)?;
+        let j_bits: usize = j_bits.parse().map_err(|_| err())?;
+        let tiers: usize = tiers.parse().map_err(|_| err())?;
+        Self::new(j_bits, tiers)
+    }
+}
+
+impl Config {
+    /// Check that J is in `1..=8` and d is at least 1.
+    pub fn new(j_bits: usize, tiers: usize) -> Result<Self, String> {
+        if !(1..=8).contains(&j_bits) || tiers == 0 {
+            return Err(format!(
+                "J{j_bits}D{tiers}: J must be in 1..=8 and d at least 1"
+            ));
+        }
+        Ok(Self { j_bits, tiers })
+    }
+}
+
+impl std::fmt::Display for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "J{}D{}", self.j_bits, self.tiers)
+    }
+}
+
+/// `None` for stdin/stdout: no path, or `-`.
+fn file_path(path: Option<&Path>) -> Option<&Path> {
+    path.filter(|path| *path != Path::new("-"))
+}
+
+/// Name the file in an error from opening it.
+pub fn with_path(path: &Path, err: io::Error) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {err}", path.display()))
+}
+
+pub fn open_input(path: Option<&Path>) -> io::Result<Box<dyn BufRead>> {
+    Ok(match file_path(path) {
+        Some(path) => Box::new(BufReader::new(
+            File::open(path).map_err(|err| with_path(path, err))?,
+        )),
+        None => Box::new(io::stdin().lock()),
+    })
+}
+
+pub fn open_output(path: Option<&Path>) -> io::Result<Box<dyn Write>> {
+    Ok(match file_path(path) {
+        Some(path) => Box::new(File::create(path).map_err(|err| with_path(path, err))?),
+        None => Box::new(io::stdout().lock()),
+    })
+}
+
+/// Exit status for input text that is not a valid integer or codeword.
+pub const EXIT_PARSE: u8 = 3;
+/// Exit status for values the configuration cannot code and data that does not decode, including
+/// corrupt containers.
+pub const EXIT_CODEC: u8 = 4;
+
+/// Why a command failed, which decides its exit status. I/O failures and everything else exit
+/// with 1, and clap exits with 2 on bad arguments.
+#[derive(Debug)]
+pub enum CliError {
+    Parse(String),
+    Codec(String),
+    Other(LotusError),
+}
+
+impl CliError {
+    /// Prefix the input position, such as `line 3`, and for codec errors the offending text.
+    pub fn at(self, position: impl fmt::Display, text: &str) -> Self {
+        match self {
+            Self::Parse(message) => Self::Parse(format!("{position}: {message}")),
+            Self::Codec(message) => Self::Codec(format!("{position}: `{text}`: {message}")),
+            other => other,
+        }
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Parse(_) => ExitCode::from(EXIT_PARSE),
+            Self::Codec(_) => ExitCode::from(EXIT_CODEC),
+            Self::Other(_) => ExitCode::FAILURE,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(message) | Self::Codec(message) => f.write_str(message),
+            Self::Other(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// Codec failures keep their own exit status; I/O errors and messages are [`CliError::Other`].
+impl From<LotusError> for CliError {
+    fn from(err: LotusError) -> Self {
+        match err {
+            LotusError::Io { .. } | LotusError::Message(_) => Self::Other(err),
+            err => Self::Codec(err.to_string()),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        LotusError::from(err).into()
+    }
+}
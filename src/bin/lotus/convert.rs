@@ -0,0 +1,155 @@
+//! `convert`: transcode LEB128 varint streams to and from `.lotus` containers.
+
+use std::io::{BufWriter, Cursor, Read, Write};
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use lotus::{
+    ContainerReader, ContainerWriter, LotusError, SequenceCodec, leb128_read, leb128_write,
+    sleb128_read, sleb128_write, zigzag_decode, zigzag_encode,
+};
+
+use crate::common::{CliError, Config, open_input, open_output};
+use crate::pack::{Codec, Counting, codec_name, ratio};
+
+/// Encoding on either side of a conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Encoding {
+    /// Unsigned LEB128 varints back to back, as in protobuf `uint64`
+    Leb128,
+    /// Signed LEB128 varints back to back; zigzagged inside the container
+    Sleb128,
+    /// A `.lotus` container
+    Lotus,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Leb128 => "leb128",
+            Self::Sleb128 => "sleb128",
+            Self::Lotus => "lotus",
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// Data to convert; stdin when omitted or `-`
+    input: Option<PathBuf>,
+    /// Where to write the result; stdout when omitted or `-`
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    #[arg(long, value_enum)]
+    from: Encoding,
+    #[arg(long, value_enum)]
+    to: Encoding,
+    /// Configuration of the container written; when reading one, fail unless it matches
+    #[arg(short, long)]
+    config: Option<Config>,
+    /// Transform applied before Lotus coding when writing a container
+    #[arg(long, value_enum, default_value_t = Codec::Plain)]
+    codec: Codec,
+}
+
+/// Decode every varint in `bytes`, reporting the byte offset of the first malformed one.
+fn read_varints(
+    bytes: &[u8],
+    signed: bool,
+    mut push: impl FnMut(u64) -> Result<(), LotusError>,
+) -> Result<u64, CliError> {
+    let (mut pos, mut count) = (0, 0u64);
+    while pos < bytes.len() {
+        let start = pos;
+        let value = if signed {
+            sleb128_read(bytes, &mut pos).map(zigzag_encode)
+        } else {
+            leb128_read(bytes, &mut pos)
+        };
+        let value = value.map_err(|err| {
+            let problem = match err {
+                LotusError::UnexpectedEof => "truncated".to_string(),
+                LotusError::InvalidEncoding => "overlong or wider than 64 bits".to_string(),
+                err => err.to_string(),
+            };
+            CliError::Codec(format!("varint {count} at byte {start} is {problem}"))
+        })?;
+        push(value)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+pub fn convert(args: ConvertArgs) -> Result<(), CliError> {
+    let mut input = Vec::new();
+    open_input(args.input.as_deref())?.read_to_end(&mut input)?;
+    let mut output = Counting {
+        inner: BufWriter::new(open_output(args.output.as_deref())?),
+        bytes: 0,
+    };
+    let (config, codec, count) = match (args.from, args.to) {
+        (Encoding::Leb128 | Encoding::Sleb128, Encoding::Lotus) => {
+            let config = args.config.unwrap_or(Config {
+                j_bits: 2,
+                tiers: 1,
+            });
+            let codec = SequenceCodec::from(args.codec);
+            let mut writer = ContainerWriter::new(&mut output, config.j_bits, config.tiers, codec)?;
+            let signed = args.from == Encoding::Sleb128;
+            let count = read_varints(&input, signed, |value| writer.push(value))?;
+            writer.finish()?;
+            (config, codec, count)
+        }
+        (Encoding::Lotus, Encoding::Leb128 | Encoding::Sleb128) => {
+            let mut reader = ContainerReader::new(Cursor::new(&input))?;
+            let config = Config {
+                j_bits: reader.j_bits(),
+                tiers: reader.tiers(),
+            };
+            if let Some(expected) = args.config
+                && expected != config
+            {
+                return Err(LotusError::Message(format!(
+                    "container was packed with {config}, not {expected}"
+                ))
+                .into());
+            }
+            let mut varints = Vec::new();
+            for index in 0..reader.block_count() {
+                varints.clear();
+                for value in reader.read_block(index)? {
+                    match args.to {
+                        Encoding::Sleb128 => sleb128_write(&mut varints, zigzag_decode(value)),
+                        _ => leb128_write(&mut varints, value),
+                    }
+                }
+                output.write_all(&varints)?;
+            }
+            (config, reader.codec(), reader.len())
+        }
+        (from, to) => {
+            return Err(LotusError::Message(format!(
+                "cannot convert {} to {}: one side must be lotus and the other a varint",
+                from.name(),
+                to.name()
+            ))
+            .into());
+        }
+    };
+    output.flush()?;
+    let (input_bytes, output_bytes) = (input.len() as u64, output.bytes);
+    let change = match input_bytes {
+        0 => 0.0,
+        _ => 100.0 * (ratio(output_bytes, input_bytes) - 1.0),
+    };
+    eprintln!(
+        "converted {count} values ({} -> {}, {config}, {}): {input_bytes} -> {output_bytes} bytes, {:.2} bits/value, {:.1}% {}",
+        args.from.name(),
+        args.to.name(),
+        codec_name(codec),
+        ratio(output_bytes * 8, count),
+        change.abs(),
+        if change <= 0.0 { "smaller" } else { "larger" },
+    );
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! `explain`: annotated breakdown of one codeword.
+
+use clap::Args;
+use lotus::{Codeword, CodewordField};
+
+use crate::common::{CliError, Config};
+
+#[derive(Args)]
+pub struct ExplainArgs {
+    /// Value to encode and explain
+    #[arg(required_unless_present = "hex", conflicts_with = "hex")]
+    value: Option<u64>,
+    /// Explain the codeword at the start of these hex bytes instead
+    #[arg(long)]
+    hex: Option<String>,
+    /// Lotus configuration
+    #[arg(short, long, default_value = "J2D1")]
+    config: Config,
+}
+
+/// One table row: bit range, field name, raw bits and what they mean.
+fn row(name: &str, field: &CodewordField, bits_column: usize, meaning: &str) -> String {
+    let range = format!("{}..{}", field.offset, field.offset + field.width);
+    let bits = format!("{:0width$b}", field.bits, width = field.width);
+    format!("{range:>9}  {name:<11}  {bits:<bits_column$}  {meaning}")
+}
+
+fn render(codeword: &Codeword, config: Config) -> String {
+    let fields = std::iter::once(&codeword.jumpstarter)
+        .chain(&codeword.tier_fields)
+        .chain(std::iter::once(&codeword.payload));
+    let bits_column = fields.map(|field| field.width).max().unwrap_or(0).max(4);
+    let mut lines = vec![
+        format!(
+            "{} under {config}: {} bits, {} bytes padded",
+            codeword.value(),
+            codeword.bit_len(),
+            codeword.bit_len().div_ceil(8)
+        ),
+        format!(
+            "{:>9}  {:<11}  {:<bits_column$}  meaning",
+            "bits", "field", "raw"
+        ),
+    ];
+    // The jumpstarter sizes tier 1, each tier the next one, and the last tier the payload.
+    let sized = |index: usize| {
+        if index < codeword.tier_fields.len() {
+            format!("tier {}", index + 1)
+        } else {
+            "payload".to_string()
+        }
+    };
+    lines.push(row(
+        "jumpstarter",
+        &codeword.jumpstarter,
+        bits_column,
+        &format!(
+            "{} is {} bits (stored minus one)",
+            sized(0),
+            codeword.jumpstarter.decoded
+        ),
+    ));
+    for (index, field) in codeword.tier_fields.iter().enumerate() {
+        lines.push(row(
+            &format!("tier {}", index + 1),
+            field,
+            bits_column,
+            &format!("{} is {} bits", sized(index + 1), field.decoded),
+        ));
+    }
+    lines.push(row(
+        "payload",
+        &codeword.payload,
+        bits_column,
+        &format!("value {}", codeword.payload.decoded),
+    ));
+    lines.join("\n")
+}
+
+pub fn explain(args: ExplainArgs) -> Result<(), CliError> {
+    let config = args.config;
+    let (codeword, input_bits) = match (&args.hex, args.value) {
+        (Some(hex), _) => {
+            let bytes = hex::decode(hex.trim())
+                .map_err(|err| CliError::Parse(format!("invalid hex input: {err}")))?;
+            let codeword = Codeword::decode(&bytes, config.j_bits, config.tiers)?;
+            (codeword, Some(bytes.len() * 8))
+        }
+        (None, Some(value)) => (Codeword::encode(value, config.j_bits, config.tiers)?, None),
+        (None, None) => unreachable!("clap requires a value or --hex"),
+    };
+    println!("{}", render(&codeword, config));
+    if let Some(input_bits) = input_bits {
+        println!(
+            "{} of {input_bits} input bits follow the codeword",
+            input_bits - codeword.bit_len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,436 @@
+//! Text and binary formats for integers and codewords on the command line.
+//!
+//! Integers are read and written as decimal, hex or binary lines, a JSON array, or one column of
+//! a CSV file. Codewords are hex, base64 or binary-bitstring lines, a JSON array of hex strings,
+//! or raw bytes with each codeword padded to a whole byte.
+//!
+//! Line formats are streamed one line at a time. Blank lines and lines starting with `#` are
+//! ignored, and errors name the line number and its text.
+
+use std::io::{BufRead, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::{Args, ValueEnum};
+use lotus::{
+    BitReader, BitWriter, Codeword, LotusError, ReadLotusExt, lotus_encode_u64, lotus_read_u64,
+};
+
+use crate::common::{CliError, Config};
+
+/// How integers are written as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ValueFormat {
+    /// One decimal integer per line
+    Decimal,
+    /// One hex integer per line, with or without `0x`
+    Hex,
+    /// One binary integer per line, with or without `0b`
+    Binary,
+    /// A JSON array of integers
+    Json,
+    /// One column of a CSV file, chosen with `--column`; written as one column with no header
+    Csv,
+}
+
+/// How codewords are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CodewordFormat {
+    /// One byte-padded codeword per line as hex
+    Hex,
+    /// One codeword per line as its exact bits, without padding
+    Binary,
+    /// One byte-padded codeword per line as base64
+    Base64,
+    /// Byte-padded codewords back to back, with no separators
+    Raw,
+    /// A JSON array of hex strings, one per codeword
+    Json,
+}
+
+/// A CSV column: a 1-based index, or a header name (the first row is then the header).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl std::str::FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<usize>() {
+            Ok(0) => Err("CSV columns are numbered from 1".to_string()),
+            Ok(index) => Ok(Self::Index(index)),
+            Err(_) => Ok(Self::Name(s.to_string())),
+        }
+    }
+}
+
+/// What to do with an input line that does not parse or that the codec rejects.
+#[derive(Debug, Clone, Copy, Args)]
+pub struct LinePolicy {
+    /// Report invalid lines on stderr and carry on with the next one
+    #[arg(long, conflicts_with = "strict")]
+    pub skip_invalid: bool,
+    /// Stop at the first invalid line (the default)
+    #[arg(long)]
+    pub strict: bool,
+}
+
+impl LinePolicy {
+    /// Pass `err` on, or report and swallow it when skipping invalid input.
+    fn handle(self, err: CliError) -> Result<(), CliError> {
+        match err {
+            CliError::Parse(_) | CliError::Codec(_) if self.skip_invalid => {
+                eprintln!("lotus: skipped {err}");
+                Ok(())
+            }
+            err => Err(err),
+        }
+    }
+}
+
+/// Options for reading integers, shared by every command that takes a dataset.
+#[derive(Args)]
+pub struct ValueInput {
+    /// Format of the input integers
+    #[arg(long, value_enum, default_value_t = ValueFormat::Decimal)]
+    pub input_format: ValueFormat,
+    /// CSV column to read (1-based index or header name)
+    #[arg(long, default_value = "1")]
+    pub column: Column,
+    #[command(flatten)]
+    pub policy: LinePolicy,
+}
+
+fn invalid(what: &str, text: &str) -> CliError {
+    CliError::Parse(format!("invalid {what} `{text}`"))
+}
+
+/// Blank lines and `#` comments carry no data.
+fn is_skipped(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+fn parse_integer(text: &str, format: ValueFormat) -> Result<u64, CliError> {
+    let text = text.trim();
+    let parsed = match format {
+        ValueFormat::Hex => u64::from_str_radix(
+            text.strip_prefix("0x")
+                .or_else(|| text.strip_prefix("0X"))
+                .unwrap_or(text),
+            16,
+        ),
+        ValueFormat::Binary => u64::from_str_radix(
+            text.strip_prefix("0b")
+                .or_else(|| text.strip_prefix("0B"))
+                .unwrap_or(text),
+            2,
+        ),
+        ValueFormat::Decimal | ValueFormat::Json | ValueFormat::Csv => text.parse(),
+    };
+    parsed.map_err(|_| invalid("integer", text))
+}
+
+/// Items of a flat JSON array, as raw token text (strings keep their quotes).
+fn json_items(text: &str) -> Result<Vec<&str>, CliError> {
+    let inner = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| CliError::Parse("expected a JSON array".to_string()))?;
+    if inner.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(inner.split(',').map(str::trim).collect())
+}
+
+fn json_string(item: &str) -> Result<&str, CliError> {
+    item.strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| invalid("JSON string", item))
+}
+
+/// Split one CSV record, honouring double-quoted fields with `""` escapes.
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Read integers in `options.input_format`, returning the number of input bytes consumed.
+///
+/// Errors from `push` are attributed to the line that produced the value, and are skipped like
+/// parse errors under `--skip-invalid`, so `push` must leave its state unchanged when it rejects
+/// a value.
+pub fn read_values(
+    mut input: impl BufRead,
+    options: &ValueInput,
+    mut push: impl FnMut(u64) -> Result<(), LotusError>,
+) -> Result<u64, CliError> {
+    let format = options.input_format;
+    let mut accept = |position: String, text: &str| {
+        let result = parse_integer(text, format).and_then(|value| Ok(push(value)?));
+        match result {
+            Err(err) => options.policy.handle(err.at(position, text.trim())),
+            Ok(()) => Ok(()),
+        }
+    };
+    if format == ValueFormat::Json {
+        let mut text = String::new();
+        input.read_to_string(&mut text)?;
+        for (index, item) in json_items(&text)?.into_iter().enumerate() {
+            accept(format!("item {}", index + 1), item)?;
+        }
+        return Ok(text.len() as u64);
+    }
+
+    let mut text_bytes = 0u64;
+    let mut column = match &options.column {
+        Column::Index(index) => Some(index - 1),
+        Column::Name(_) => None,
+    };
+    for (number, line) in (1u64..).zip(input.lines()) {
+        let line = line?;
+        text_bytes += line.len() as u64 + 1;
+        if is_skipped(&line) {
+            continue;
+        }
+        let position = format!("line {number}");
+        if format != ValueFormat::Csv {
+            accept(position, &line)?;
+            continue;
+        }
+        let fields = csv_fields(&line);
+        let Some(index) = column else {
+            let Column::Name(name) = &options.column else {
+                unreachable!("only named columns are resolved from the header");
+            };
+            let index = fields.iter().position(|field| field.trim() == name);
+            column = Some(index.ok_or_else(|| {
+                CliError::Other(LotusError::Message(format!("no CSV column named `{name}`")))
+            })?);
+            continue;
+        };
+        match fields.get(index) {
+            Some(field) => accept(position, field)?,
+            None => {
+                let err = CliError::Parse(format!("CSV row has no column {}", index + 1));
+                options.policy.handle(err.at(position, &line))?;
+            }
+        }
+    }
+    Ok(text_bytes)
+}
+
+/// Writes integers in one [`ValueFormat`]; call [`finish`](Self::finish) to close JSON arrays.
+pub struct ValueWriter<W: Write> {
+    out: W,
+    format: ValueFormat,
+    started: bool,
+}
+
+impl<W: Write> ValueWriter<W> {
+    pub fn new(out: W, format: ValueFormat) -> Self {
+        Self {
+            out,
+            format,
+            started: false,
+        }
+    }
+
+    pub fn write(&mut self, value: u64) -> Result<(), LotusError> {
+        let first = !self.started;
+        self.started = true;
+        match self.format {
+            ValueFormat::Hex => writeln!(self.out, "{value:x}")?,
+            ValueFormat::Binary => writeln!(self.out, "{value:b}")?,
+            ValueFormat::Json => write!(self.out, "{}{value}", if first { "[" } else { "," })?,
+            ValueFormat::Decimal | ValueFormat::Csv => writeln!(self.out, "{value}")?,
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W, LotusError> {
+        match (self.format, self.started) {
+            (ValueFormat::Json, true) => writeln!(self.out, "]")?,
+            (ValueFormat::Json, false) => writeln!(self.out, "[]")?,
+            _ => {}
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Parse a string of `0`/`1` characters into a packed, zero-padded bitstream.
+fn parse_bitstring(text: &str) -> Result<(Vec<u8>, usize), CliError> {
+    let text = text.trim();
+    let mut writer = BitWriter::new();
+    for c in text.chars() {
+        match c {
+            '0' | '1' => writer.write_bits(u64::from(c == '1'), 1)?,
+            _ => return Err(invalid("bitstring", text)),
+        }
+    }
+    let bits = writer.bits_written();
+    Ok((writer.into_bytes(), bits))
+}
+
+fn decode_one(bytes: &[u8], config: Config) -> Result<u64, CliError> {
+    Ok(lotus_read_u64(
+        &mut BitReader::new(bytes),
+        config.j_bits,
+        config.tiers,
+    )?)
+}
+
+/// Decode one codeword written as text in `format`.
+fn decode_text(text: &str, format: CodewordFormat, config: Config) -> Result<u64, CliError> {
+    match format {
+        CodewordFormat::Hex | CodewordFormat::Json => {
+            let bytes = hex::decode(text).map_err(|_| invalid("hex codeword", text))?;
+            decode_one(&bytes, config)
+        }
+        CodewordFormat::Base64 => {
+            let bytes = BASE64
+                .decode(text)
+                .map_err(|_| invalid("base64 codeword", text))?;
+            decode_one(&bytes, config)
+        }
+        CodewordFormat::Binary => {
+            let (bytes, bits) = parse_bitstring(text)?;
+            let mut reader = BitReader::new(&bytes);
+            let value = lotus_read_u64(&mut reader, config.j_bits, config.tiers)?;
+            if reader.bits_consumed() != bits {
+                return Err(CliError::Codec(format!(
+                    "{} bits follow the codeword",
+                    bits - reader.bits_consumed()
+                )));
+            }
+            Ok(value)
+        }
+        CodewordFormat::Raw => unreachable!("raw codewords are not text"),
+    }
+}
+
+/// Read codewords in `format`, passing each decoded value to `push`.
+///
+/// Raw input has no lines to resynchronise on, so its first bad codeword always stops the read.
+pub fn read_codewords(
+    mut input: impl BufRead,
+    format: CodewordFormat,
+    config: Config,
+    policy: LinePolicy,
+    mut push: impl FnMut(u64) -> Result<(), LotusError>,
+) -> Result<(), CliError> {
+    let mut accept = |position: String, text: &str| {
+        let result = decode_text(text, format, config).and_then(|value| Ok(push(value)?));
+        match result {
+            Err(err) => policy.handle(err.at(position, text)),
+            Ok(()) => Ok(()),
+        }
+    };
+    match format {
+        CodewordFormat::Raw => {
+            for number in 1u64.. {
+                if input.fill_buf()?.is_empty() {
+                    break;
+                }
+                let value = input
+                    .read_lotus_u64(config.j_bits, config.tiers)
+                    .map_err(|err| CliError::from(LotusError::from(err)))
+                    .map_err(|err| err.at(format!("codeword {number}"), "raw"))?;
+                push(value)?;
+            }
+        }
+        CodewordFormat::Json => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            for (index, item) in json_items(&text)?.into_iter().enumerate() {
+                let position = format!("item {}", index + 1);
+                match json_string(item) {
+                    Ok(hex_text) => accept(position, hex_text)?,
+                    Err(err) => policy.handle(err.at(position, item))?,
+                }
+            }
+        }
+        CodewordFormat::Hex | CodewordFormat::Base64 | CodewordFormat::Binary => {
+            for (number, line) in (1u64..).zip(input.lines()) {
+                let line = line?;
+                if !is_skipped(&line) {
+                    accept(format!("line {number}"), line.trim())?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes codewords in one [`CodewordFormat`]; call [`finish`](Self::finish) to close JSON arrays.
+pub struct CodewordWriter<W: Write> {
+    out: W,
+    format: CodewordFormat,
+    config: Config,
+    started: bool,
+}
+
+impl<W: Write> CodewordWriter<W> {
+    pub fn new(out: W, format: CodewordFormat, config: Config) -> Self {
+        Self {
+            out,
+            format,
+            config,
+            started: false,
+        }
+    }
+
+    /// Write the codeword for `value`; a value the configuration cannot code writes nothing.
+    pub fn write(&mut self, value: u64) -> Result<(), LotusError> {
+        let (j_bits, tiers) = (self.config.j_bits, self.config.tiers);
+        if self.format == CodewordFormat::Binary {
+            let codeword = Codeword::encode(value, j_bits, tiers)?;
+            let line: String = std::iter::once(&codeword.jumpstarter)
+                .chain(&codeword.tier_fields)
+                .chain(std::iter::once(&codeword.payload))
+                .map(|field| format!("{:0width$b}", field.bits, width = field.width))
+                .collect();
+            writeln!(self.out, "{line}")?;
+            return Ok(());
+        }
+        let bytes = lotus_encode_u64(value, j_bits, tiers)?;
+        let separator = if self.started { "," } else { "[" };
+        self.started = true;
+        match self.format {
+            CodewordFormat::Hex => writeln!(self.out, "{}", hex::encode(bytes))?,
+            CodewordFormat::Base64 => writeln!(self.out, "{}", BASE64.encode(bytes))?,
+            CodewordFormat::Raw => self.out.write_all(&bytes)?,
+            CodewordFormat::Json => write!(self.out, "{separator}\"{}\"", hex::encode(bytes))?,
+            CodewordFormat::Binary => unreachable!("written above"),
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W, LotusError> {
+        match (self.format, self.started) {
+            (CodewordFormat::Json, true) => writeln!(self.out, "]")?,
+            (CodewordFormat::Json, false) => writeln!(self.out, "[]")?,
+            _ => {}
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
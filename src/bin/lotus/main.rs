@@ -0,0 +1,145 @@
+mod analyze;
+mod benchmark;
+mod common;
+mod convert;
+mod explain;
+mod formats;
+mod pack;
+mod selfcheck;
+mod table;
+mod verify;
+
+use clap::{Parser, Subcommand};
+use formats::{CodewordFormat, CodewordWriter, LinePolicy, ValueFormat, ValueInput, ValueWriter};
+use lotus::LotusError;
+use std::io::{self, BufWriter};
+use std::process::ExitCode;
+
+use common::{CliError, Config};
+
+#[derive(Parser)]
+#[command(author, version, about = "Lotus integer codec CLI", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Encode integers read from stdin, one codeword each
+    Encode {
+        #[arg(short, long, default_value_t = 2)]
+        jumpstarter: usize,
+        #[arg(short, long, default_value_t = 1)]
+        tiers: usize,
+        #[command(flatten)]
+        values: ValueInput,
+        /// Format of the output codewords
+        #[arg(long, value_enum, default_value_t = CodewordFormat::Hex)]
+        output_format: CodewordFormat,
+    },
+    /// Decode codewords read from stdin
+    Decode {
+        #[arg(short, long, default_value_t = 2)]
+        jumpstarter: usize,
+        #[arg(short, long, default_value_t = 1)]
+        tiers: usize,
+        /// Format of the input codewords
+        #[arg(long, value_enum, default_value_t = CodewordFormat::Hex)]
+        input_format: CodewordFormat,
+        #[command(flatten)]
+        policy: LinePolicy,
+        /// Format of the output integers
+        #[arg(long, value_enum, default_value_t = ValueFormat::Decimal)]
+        output_format: ValueFormat,
+    },
+    /// Pack integers (one per line) into a `.lotus` container
+    Pack(pack::PackArgs),
+    /// Unpack a `.lotus` container to one integer per line
+    Unpack(pack::UnpackArgs),
+    /// Report the distribution of a dataset and its cost under each coding scheme
+    Analyze(analyze::AnalyzeArgs),
+    /// Show the fields of one codeword with their bit offsets
+    Explain(explain::ExplainArgs),
+    /// Compare sizes and throughput against LEB128 and Elias codes on real or synthetic data
+    Benchmark(benchmark::BenchmarkArgs),
+    /// List the value range and codeword size of each payload width of a configuration
+    Table(table::TableArgs),
+    /// Strictly decode a `.lotus` container, locating the first corrupt codeword
+    Verify(verify::VerifyArgs),
+    /// Transcode LEB128 or SLEB128 varints to a `.lotus` container and back
+    Convert(convert::ConvertArgs),
+    /// Enumerate every short codeword, checking prefix-freeness and the value mapping
+    Selfcheck(selfcheck::SelfcheckArgs),
+}
+
+fn encode_mode(
+    config: Config,
+    values: &ValueInput,
+    format: CodewordFormat,
+) -> Result<(), CliError> {
+    let mut out = CodewordWriter::new(BufWriter::new(io::stdout().lock()), format, config);
+    formats::read_values(io::stdin().lock(), values, |value| out.write(value))?;
+    out.finish()?;
+    Ok(())
+}
+
+fn decode_mode(
+    config: Config,
+    input: CodewordFormat,
+    policy: LinePolicy,
+    output: ValueFormat,
+) -> Result<(), CliError> {
+    let mut out = ValueWriter::new(BufWriter::new(io::stdout().lock()), output);
+    formats::read_codewords(io::stdin().lock(), input, config, policy, |value| {
+        out.write(value)
+    })?;
+    out.finish()?;
+    Ok(())
+}
+
+/// `-j`/`-t` are plain numbers, so they are checked here rather than by clap.
+fn config(j_bits: usize, tiers: usize) -> Result<Config, CliError> {
+    Config::new(j_bits, tiers).map_err(|message| CliError::Other(LotusError::Message(message)))
+}
+
+fn run(command: Command) -> Result<ExitCode, CliError> {
+    match command {
+        Command::Encode {
+            jumpstarter,
+            tiers,
+            values,
+            output_format,
+        } => encode_mode(config(jumpstarter, tiers)?, &values, output_format)?,
+        Command::Decode {
+            jumpstarter,
+            tiers,
+            input_format,
+            policy,
+            output_format,
+        } => decode_mode(
+            config(jumpstarter, tiers)?,
+            input_format,
+            policy,
+            output_format,
+        )?,
+        Command::Pack(args) => pack::pack(args)?,
+        Command::Unpack(args) => pack::unpack(args)?,
+        Command::Analyze(args) => analyze::analyze(args)?,
+        Command::Explain(args) => explain::explain(args)?,
+        Command::Benchmark(args) => benchmark::benchmark(args)?,
+        Command::Table(args) => table::table(args)?,
+        Command::Verify(args) => return Ok(verify::verify(args)?),
+        Command::Convert(args) => convert::convert(args)?,
+        Command::Selfcheck(args) => selfcheck::selfcheck(args)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    run(cli.command).unwrap_or_else(|err| {
+        eprintln!("lotus: {err}");
+        err.exit_code()
+    })
+}
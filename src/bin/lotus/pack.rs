@@ -0,0 +1,162 @@
+//! `pack` / `unpack`: one decimal integer per line to and from a `.lotus` container.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use lotus::{ContainerReader, ContainerWriter, LotusError, SequenceCodec};
+
+use crate::common::{CliError, Config, open_input, open_output, with_path};
+use crate::formats::{ValueFormat, ValueInput, ValueWriter, read_values};
+
+/// Sequence codec applied inside the container.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Codec {
+    Plain,
+    Delta,
+    For,
+    Rle,
+}
+
+impl From<Codec> for SequenceCodec {
+    fn from(codec: Codec) -> Self {
+        match codec {
+            Codec::Plain => SequenceCodec::Plain,
+            Codec::Delta => SequenceCodec::Delta,
+            Codec::For => SequenceCodec::FrameOfReference,
+            Codec::Rle => SequenceCodec::Rle,
+        }
+    }
+}
+
+pub fn codec_name(codec: SequenceCodec) -> &'static str {
+    match codec {
+        SequenceCodec::Plain => "plain",
+        SequenceCodec::Delta => "delta",
+        SequenceCodec::FrameOfReference => "for",
+        SequenceCodec::Rle => "rle",
+    }
+}
+
+#[derive(Args)]
+pub struct PackArgs {
+    /// Integers to pack; stdin when omitted or `-`
+    input: Option<PathBuf>,
+    #[command(flatten)]
+    values: ValueInput,
+    /// Container to write; stdout when omitted or `-`
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Lotus configuration for the values
+    #[arg(short, long, default_value = "J2D1")]
+    config: Config,
+    /// Transform applied before Lotus coding
+    #[arg(long, value_enum, default_value_t = Codec::Plain)]
+    codec: Codec,
+}
+
+#[derive(Args)]
+pub struct UnpackArgs {
+    /// Container to read
+    input: PathBuf,
+    /// Where to write the integers; stdout when omitted or `-`
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Format of the output integers
+    #[arg(long, value_enum, default_value_t = ValueFormat::Decimal)]
+    output_format: ValueFormat,
+    /// Fail unless the container was packed with this configuration
+    #[arg(short, long)]
+    config: Option<Config>,
+}
+
+/// Counts bytes passed through to the inner writer.
+pub struct Counting<W> {
+    pub inner: W,
+    pub bytes: u64,
+}
+
+impl<W: Write> Write for Counting<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+pub fn pack(args: PackArgs) -> Result<(), CliError> {
+    let input = open_input(args.input.as_deref())?;
+    let output = Counting {
+        inner: BufWriter::new(open_output(args.output.as_deref())?),
+        bytes: 0,
+    };
+    let config = args.config;
+    let codec = SequenceCodec::from(args.codec);
+    let mut writer = ContainerWriter::new(output, config.j_bits, config.tiers, codec)?;
+    let mut count = 0u64;
+    let text_bytes = read_values(input, &args.values, |value| {
+        writer.push(value)?;
+        count += 1;
+        Ok(())
+    })?;
+    let mut output = writer.finish()?;
+    output.flush()?;
+    eprintln!(
+        "packed {count} values ({config}, {}): {text_bytes} -> {} bytes, {:.2} bits/value, {:.2}x",
+        codec_name(codec),
+        output.bytes,
+        ratio(output.bytes * 8, count),
+        ratio(text_bytes, output.bytes),
+    );
+    Ok(())
+}
+
+pub fn unpack(args: UnpackArgs) -> Result<(), LotusError> {
+    let file = File::open(&args.input).map_err(|err| with_path(&args.input, err))?;
+    let packed_bytes = file.metadata()?.len();
+    let mut reader = ContainerReader::new(BufReader::new(file))?;
+    let config = Config {
+        j_bits: reader.j_bits(),
+        tiers: reader.tiers(),
+    };
+    if let Some(expected) = args.config
+        && expected != config
+    {
+        return Err(LotusError::Message(format!(
+            "container was packed with {config}, not {expected}"
+        )));
+    }
+    let output = Counting {
+        inner: BufWriter::new(open_output(args.output.as_deref())?),
+        bytes: 0,
+    };
+    let mut values = ValueWriter::new(output, args.output_format);
+    for index in 0..reader.block_count() {
+        for value in reader.read_block(index)? {
+            values.write(value)?;
+        }
+    }
+    let output = values.finish()?;
+    eprintln!(
+        "unpacked {} values ({config}, {}): {packed_bytes} -> {} bytes, {:.2} bits/value, {:.2}x",
+        reader.len(),
+        codec_name(reader.codec()),
+        output.bytes,
+        ratio(packed_bytes * 8, reader.len()),
+        ratio(output.bytes, packed_bytes),
+    );
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! `selfcheck`: enumerate every short codeword of each configuration and check the code is sound.
+
+use std::io::Write;
+
+use clap::Args;
+use lotus::{ANALYSIS_MAX_J_BITS, ANALYSIS_MAX_TIERS, ConfigCheck, MAX_CHECK_BITS, verify_config};
+
+use crate::common::{CliError, Config};
+
+#[derive(Args)]
+pub struct SelfcheckArgs {
+    /// Configurations to check [default: every one with J up to 8 and d up to 4]
+    #[arg(short, long = "config", value_delimiter = ',')]
+    configs: Vec<Config>,
+    /// Longest codeword to enumerate, in bits
+    #[arg(long, default_value_t = 16, value_parser = clap::value_parser!(u8).range(1..=MAX_CHECK_BITS as i64))]
+    max_bits: u8,
+}
+
+fn render_row(check: &ConfigCheck) -> String {
+    format!(
+        "{:<6}  {:>9}  {:>14}  {:>10.6}  {:>8.4}%  {:>10.4}%",
+        Config {
+            j_bits: check.j_bits,
+            tiers: check.tiers
+        }
+        .to_string(),
+        check.codewords,
+        match check.codewords {
+            0 => "-".to_string(),
+            count => format!("0..={}", count - 1),
+        },
+        check.kraft_sum(),
+        check.unused_fraction() * 100.0,
+        (check.kraft_deficit() - check.unused_fraction()) * 100.0
+    )
+}
+
+pub fn selfcheck(args: SelfcheckArgs) -> Result<(), CliError> {
+    let configs = if args.configs.is_empty() {
+        (1..=ANALYSIS_MAX_J_BITS)
+            .flat_map(|j_bits| (1..=ANALYSIS_MAX_TIERS).map(move |tiers| Config { j_bits, tiers }))
+            .collect()
+    } else {
+        args.configs
+    };
+    let max_bits = usize::from(args.max_bits);
+
+    let mut out = std::io::stdout().lock();
+    writeln!(
+        out,
+        "{:<6}  {:>9}  {:>14}  {:>10}  {:>9}  {:>11}",
+        "config", "codewords", "values", "kraft sum", "unused", "unexplored"
+    )?;
+    let mut failed = 0;
+    for config in configs {
+        match verify_config(config.j_bits, config.tiers, max_bits) {
+            Ok(check) => writeln!(out, "{}", render_row(&check))?,
+            Err(err) => {
+                eprintln!("lotus: {err}");
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(CliError::Codec(format!(
+            "{failed} configuration(s) failed the self-check"
+        )));
+    }
+    writeln!(
+        out,
+        "every codeword up to {max_bits} bits is prefix-free and round-trips"
+    )?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! `table`: the size envelope of one configuration, or a summary of every configuration.
+
+use std::io::Write;
+
+use clap::Args;
+use lotus::{ANALYSIS_MAX_J_BITS, ANALYSIS_MAX_TIERS, EnvelopeRow, LotusError, lotus_envelope};
+
+use crate::common::Config;
+
+#[derive(Args)]
+pub struct TableArgs {
+    /// Configuration to tabulate
+    #[arg(short, long, default_value = "J2D1")]
+    config: Config,
+    /// Summarize every configuration with J up to 8 and d up to 4 instead
+    #[arg(long, conflicts_with = "config")]
+    all: bool,
+}
+
+fn render_config(config: Config, rows: &[EnvelopeRow]) -> String {
+    let mut lines = vec![format!(
+        "{:>7}  {:>45}  {:<24}  {:>5}",
+        "payload", "values", "tier widths", "bits"
+    )];
+    for row in rows {
+        let tiers: Vec<String> = row.tier_widths.iter().map(usize::to_string).collect();
+        lines.push(format!(
+            "{:>7}  {:>45}  {:<24}  {:>5}",
+            row.payload_width,
+            format!("{}..={}", row.min_value, row.max_value),
+            tiers.join(" "),
+            row.codeword_bits
+        ));
+    }
+    let max_value = rows.last().map_or(0, |row| row.max_value);
+    lines.push(format!("{config} max value: {max_value}"));
+    lines.join("\n")
+}
+
+fn render_all() -> Result<String, LotusError> {
+    let mut lines = vec![format!(
+        "{:<6}  {:>6}  {:>8}  {:>8}  {:>20}",
+        "config", "widths", "min bits", "max bits", "max value"
+    )];
+    for j_bits in 1..=ANALYSIS_MAX_J_BITS {
+        for tiers in 1..=ANALYSIS_MAX_TIERS {
+            let rows = lotus_envelope(j_bits, tiers)?;
+            let (Some(first), Some(last)) = (rows.first(), rows.last()) else {
+                continue;
+            };
+            lines.push(format!(
+                "{:<6}  {:>6}  {:>8}  {:>8}  {:>20}",
+                Config { j_bits, tiers }.to_string(),
+                rows.len(),
+                first.codeword_bits,
+                last.codeword_bits,
+                last.max_value
+            ));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+pub fn table(args: TableArgs) -> Result<(), LotusError> {
+    let report = if args.all {
+        render_all()?
+    } else {
+        let config = args.config;
+        render_config(config, &lotus_envelope(config.j_bits, config.tiers)?)
+    };
+    writeln!(std::io::stdout().lock(), "{report}")?;
+    Ok(())
+}
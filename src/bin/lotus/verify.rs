@@ -0,0 +1,70 @@
+//! `verify`: strictly decode a `.lotus` container and locate the first corruption.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+use lotus::{ContainerReader, LotusError};
+
+use crate::common::{Config, EXIT_CODEC};
+use crate::pack::codec_name;
+
+#[derive(Args)]
+pub struct VerifyArgs {
+    /// Container to check
+    input: PathBuf,
+    /// Fail unless the container was packed with this configuration
+    #[arg(short, long)]
+    config: Option<Config>,
+}
+
+pub fn verify(args: VerifyArgs) -> Result<ExitCode, LotusError> {
+    let path = args.input.display();
+    let file = File::open(&args.input)
+        .map_err(|err| LotusError::Message(format!("cannot open {path}: {err}")))?;
+    let mut reader = match ContainerReader::new(BufReader::new(file)) {
+        Ok(reader) => reader,
+        Err(LotusError::Io { message, .. }) => {
+            return Err(LotusError::Message(format!(
+                "cannot read {path}: {message}"
+            )));
+        }
+        Err(err) => {
+            eprintln!("{path}: corrupt header or footer: {err}");
+            return Ok(ExitCode::from(EXIT_CODEC));
+        }
+    };
+    let config = Config {
+        j_bits: reader.j_bits(),
+        tiers: reader.tiers(),
+    };
+    if let Some(expected) = args.config
+        && expected != config
+    {
+        return Err(LotusError::Message(format!(
+            "{path} was packed with {config}, not {expected}"
+        )));
+    }
+    let verification = reader.verify()?;
+    if let Some(corruption) = verification.corruption {
+        eprintln!(
+            "{path}: corrupt at bit {} (value {}, block {}): {}; {} of {} values verified",
+            corruption.bit_offset,
+            corruption.value_index,
+            corruption.block,
+            corruption.error,
+            verification.values,
+            reader.len(),
+        );
+        return Ok(ExitCode::from(EXIT_CODEC));
+    }
+    println!(
+        "{path}: ok, {} values in {} blocks ({config}, {})",
+        verification.values,
+        verification.blocks,
+        codec_name(reader.codec()),
+    );
+    Ok(ExitCode::SUCCESS)
+}
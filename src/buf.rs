@@ -0,0 +1,248 @@
+//! `bytes::Buf` / `bytes::BufMut` extension traits for reading and writing codewords in place.
+//!
+//! The plain methods work at byte boundaries: each codeword is padded to a whole number of bytes.
+//! The `_packed` methods thread a [`BitCarry`] between calls so consecutive codewords share bytes
+//! exactly as in a [`BitWriter`](crate::BitWriter) stream.
+
+use std::io::IoSlice;
+
+use bytes::{Buf, BufMut};
+
+use crate::{BitReader, LotusError, lotus_chain, lotus_read_u64};
+
+/// Bits of a partially filled or partially consumed byte carried between packed calls.
+///
+/// When writing, the carry holds bits not yet flushed to the buffer; when reading, it holds the
+/// unread bits of the last byte taken from the buffer. Start every packed sequence from
+/// `BitCarry::default()`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BitCarry {
+    byte: u8,
+    bits: u8,
+}
+
+impl BitCarry {
+    /// Number of carried bits (0 to 7).
+    pub fn bits(&self) -> usize {
+        self.bits as usize
+    }
+
+    /// Whether the cursor sits on a byte boundary.
+    pub fn is_aligned(&self) -> bool {
+        self.bits == 0
+    }
+}
+
+fn put_bits<B: BufMut + ?Sized>(buf: &mut B, carry: &mut BitCarry, value: u64, mut width: usize) {
+    while width > 0 {
+        let take = (8 - carry.bits as usize).min(width);
+        let part = (value >> (width - take)) & ((1 << take) - 1);
+        carry.byte = (((carry.byte as u16) << take) | part as u16) as u8;
+        carry.bits += take as u8;
+        width -= take;
+        if carry.bits == 8 {
+            buf.put_u8(carry.byte);
+            *carry = BitCarry::default();
+        }
+    }
+}
+
+/// Decode one codeword from the front of `bytes`, returning it, the bytes taken and the new carry.
+fn decode_front(
+    bytes: &[u8],
+    carry: BitCarry,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(u64, usize, BitCarry), LotusError> {
+    let mut reader = BitReader {
+        bytes,
+        byte_pos: 0,
+        pending: carry.byte,
+        pending_bits: carry.bits,
+    };
+    let value = lotus_read_u64(&mut reader, j_bits, tiers)?;
+    let carry = BitCarry {
+        byte: reader.pending,
+        bits: reader.pending_bits,
+    };
+    Ok((value, reader.byte_pos, carry))
+}
+
+/// Decode without consuming anything, gathering chunks only if the codeword straddles them.
+fn peek<B: Buf + ?Sized>(
+    buf: &B,
+    carry: BitCarry,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(u64, usize, BitCarry), LotusError> {
+    match decode_front(buf.chunk(), carry, j_bits, tiers) {
+        Err(LotusError::UnexpectedEof) if buf.chunk().len() < buf.remaining() => {}
+        result => return result,
+    }
+    let mut slices = [IoSlice::new(&[]); 16];
+    let filled = buf.chunks_vectored(&mut slices);
+    let mut gathered = Vec::new();
+    for slice in &slices[..filled] {
+        gathered.extend_from_slice(slice);
+        match decode_front(&gathered, carry, j_bits, tiers) {
+            Err(LotusError::UnexpectedEof) => continue,
+            result => return result,
+        }
+    }
+    Err(LotusError::UnexpectedEof)
+}
+
+/// Lotus codeword writers for any [`BufMut`].
+///
+/// Like the `put_*` methods of [`BufMut`], these panic if the buffer runs out of capacity.
+/// Encoding errors are reported before anything is written.
+pub trait LotusBufMutExt: BufMut {
+    /// Write one codeword, zero-padded to the next byte boundary.
+    fn put_lotus_u64(&mut self, value: u64, j_bits: usize, tiers: usize) -> Result<(), LotusError> {
+        let mut carry = BitCarry::default();
+        self.put_lotus_u64_packed(&mut carry, value, j_bits, tiers)?;
+        self.put_lotus_flush(&mut carry);
+        Ok(())
+    }
+
+    /// Write one codeword at the bit position held in `carry`, leaving any partial byte there.
+    fn put_lotus_u64_packed(
+        &mut self,
+        carry: &mut BitCarry,
+        value: u64,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        let (jump_val, chain) = lotus_chain(value, j_bits, tiers)?;
+        put_bits(self, carry, jump_val, j_bits);
+        for &(bits, width) in chain.iter().rev() {
+            put_bits(self, carry, bits, width);
+        }
+        Ok(())
+    }
+
+    /// Write out a partial byte left in `carry`, zero-padded, and reset the carry.
+    fn put_lotus_flush(&mut self, carry: &mut BitCarry) {
+        if carry.bits > 0 {
+            self.put_u8(carry.byte << (8 - carry.bits));
+            *carry = BitCarry::default();
+        }
+    }
+}
+
+impl<B: BufMut + ?Sized> LotusBufMutExt for B {}
+
+/// Lotus codeword readers for any [`Buf`].
+///
+/// The buffer only advances past bytes the codeword occupies; on error nothing is consumed.
+/// A codeword split across chunks is found through [`Buf::chunks_vectored`], so buffers whose
+/// implementation exposes only the first chunk report [`LotusError::UnexpectedEof`] in that case.
+pub trait LotusBufExt: Buf {
+    /// Read one codeword that starts on a byte boundary, consuming its padding bits too.
+    fn get_lotus_u64(&mut self, j_bits: usize, tiers: usize) -> Result<u64, LotusError> {
+        let (value, taken, _) = peek(self, BitCarry::default(), j_bits, tiers)?;
+        self.advance(taken);
+        Ok(value)
+    }
+
+    /// Read one codeword continuing from the bit position held in `carry`.
+    fn get_lotus_u64_packed(
+        &mut self,
+        carry: &mut BitCarry,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<u64, LotusError> {
+        let (value, taken, next) = peek(self, *carry, j_bits, tiers)?;
+        self.advance(taken);
+        *carry = next;
+        Ok(value)
+    }
+}
+
+impl<B: Buf + ?Sized> LotusBufExt for B {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitWriter, LOTUS_J2D1, LOTUS_J3D1, lotus_encode_u64, lotus_write_u64};
+    use bytes::{Bytes, BytesMut};
+
+    const VALUES: [u64; 8] = [0, 1, 2, 5, 300, 65_535, 1 << 40, u64::MAX];
+
+    #[test]
+    fn aligned_matches_lotus_encode_u64() {
+        let (j_bits, tiers) = LOTUS_J3D1;
+        let mut buf = BytesMut::new();
+        let mut expected = Vec::new();
+        for value in VALUES {
+            buf.put_lotus_u64(value, j_bits, tiers).unwrap();
+            expected.extend(lotus_encode_u64(value, j_bits, tiers).unwrap());
+        }
+        assert_eq!(&buf[..], &expected[..]);
+
+        let mut frozen = buf.freeze();
+        for value in VALUES {
+            assert_eq!(frozen.get_lotus_u64(j_bits, tiers).unwrap(), value);
+        }
+        assert!(!frozen.has_remaining());
+    }
+
+    #[test]
+    fn packed_matches_bit_writer() {
+        let (j_bits, tiers) = LOTUS_J2D1;
+        let values: Vec<u64> = (0..200).map(|i| i * 37 % 1_000).collect();
+        let mut writer = BitWriter::new();
+        let mut buf = BytesMut::new();
+        let mut carry = BitCarry::default();
+        for &value in &values {
+            lotus_write_u64(&mut writer, value, j_bits, tiers).unwrap();
+            buf.put_lotus_u64_packed(&mut carry, value, j_bits, tiers)
+                .unwrap();
+        }
+        buf.put_lotus_flush(&mut carry);
+        assert!(carry.is_aligned());
+        assert_eq!(&buf[..], &writer.into_bytes()[..]);
+
+        let mut read = &buf[..];
+        let mut carry = BitCarry::default();
+        for &value in &values {
+            assert_eq!(
+                read.get_lotus_u64_packed(&mut carry, j_bits, tiers)
+                    .unwrap(),
+                value
+            );
+        }
+        assert!(read.is_empty());
+        assert!(carry.bits() < 8);
+    }
+
+    #[test]
+    fn codeword_straddling_chunks() {
+        let (j_bits, tiers) = LOTUS_J3D1;
+        let bytes = lotus_encode_u64(u64::MAX - 7, j_bits, tiers).unwrap();
+        let (head, tail) = bytes.split_at(3);
+        let mut chained = Bytes::copy_from_slice(head).chain(Bytes::copy_from_slice(tail));
+        assert_eq!(chained.get_lotus_u64(j_bits, tiers).unwrap(), u64::MAX - 7);
+        assert!(!chained.has_remaining());
+    }
+
+    #[test]
+    fn errors_consume_nothing() {
+        let (j_bits, tiers) = LOTUS_J3D1;
+        let bytes = lotus_encode_u64(1 << 50, j_bits, tiers).unwrap();
+        let mut truncated = &bytes[..bytes.len() - 1];
+        assert_eq!(
+            truncated.get_lotus_u64(j_bits, tiers),
+            Err(LotusError::UnexpectedEof)
+        );
+        assert_eq!(truncated.len(), bytes.len() - 1);
+
+        let mut buf = BytesMut::new();
+        let mut carry = BitCarry::default();
+        assert_eq!(
+            buf.put_lotus_u64_packed(&mut carry, u64::MAX, 1, 1),
+            Err(LotusError::ValueTooLarge)
+        );
+        assert!(buf.is_empty() && carry.is_aligned());
+    }
+}
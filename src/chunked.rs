@@ -0,0 +1,246 @@
+//! Chunked layout: fixed-size chunks stitched into one bitstream behind a chunk offset table.
+//!
+//! Each chunk is an ordinary packed Lotus stream, so chunks can be encoded and decoded
+//! independently. The serial and `rayon` entry points share the same per-chunk routines and
+//! therefore produce bit-identical output.
+
+use crate::{BitReader, BitWriter, HEADER_CONFIG, LotusError, lotus_read_u64, lotus_write_u64};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+fn encode_chunk(values: &[u64], j_bits: usize, tiers: usize) -> Result<BitWriter, LotusError> {
+    let mut writer = BitWriter::new();
+    for &value in values {
+        lotus_write_u64(&mut writer, value, j_bits, tiers)?;
+    }
+    Ok(writer)
+}
+
+/// Write the header and offset table, then the chunk streams back to back.
+///
+/// The table stores each chunk's bit length; chunk `k` starts at the sum of the lengths before it.
+fn stitch(count: usize, chunk_len: usize, chunks: &[BitWriter]) -> Result<Vec<u8>, LotusError> {
+    let (hj, hd) = HEADER_CONFIG;
+    let mut header = BitWriter::new();
+    lotus_write_u64(&mut header, count as u64, hj, hd)?;
+    lotus_write_u64(&mut header, chunk_len as u64, hj, hd)?;
+    for chunk in chunks {
+        lotus_write_u64(&mut header, chunk.bits_written() as u64, hj, hd)?;
+    }
+    let mut body = BitWriter::new();
+    for chunk in chunks {
+        body.append(chunk)?;
+    }
+    let mut out = header.into_bytes();
+    out.extend_from_slice(&body.into_bytes());
+    Ok(out)
+}
+
+/// Chunk layout recovered from the header.
+struct ChunkTable<'a> {
+    count: usize,
+    chunk_len: usize,
+    body: &'a [u8],
+    /// Bit offset of each chunk within `body`, plus a final entry for the end of the last chunk.
+    offsets: Vec<usize>,
+}
+
+fn read_table(bytes: &[u8]) -> Result<ChunkTable<'_>, LotusError> {
+    let (hj, hd) = HEADER_CONFIG;
+    let mut reader = BitReader::new(bytes);
+    let count = lotus_read_u64(&mut reader, hj, hd)?;
+    let chunk_len = lotus_read_u64(&mut reader, hj, hd)?;
+    if chunk_len == 0 {
+        return Err(LotusError::InvalidEncoding);
+    }
+    // Each table entry takes at least one bit.
+    let chunks = count.div_ceil(chunk_len);
+    if chunks > reader.bits_remaining() as u64 {
+        return Err(LotusError::UnexpectedEof);
+    }
+    let mut offsets = Vec::with_capacity(chunks as usize + 1);
+    let mut cursor = 0usize;
+    offsets.push(cursor);
+    for _ in 0..chunks {
+        let bits = lotus_read_u64(&mut reader, hj, hd)?;
+        cursor = usize::try_from(bits)
+            .ok()
+            .and_then(|bits| cursor.checked_add(bits))
+            .ok_or(LotusError::UnexpectedEof)?;
+        offsets.push(cursor);
+    }
+    let body = &bytes[reader.bits_consumed().div_ceil(8)..];
+    // Every value takes at least one bit, so the chunks bound the count before anything is
+    // allocated for it.
+    if cursor > body.len() * 8 || count > cursor as u64 {
+        return Err(LotusError::UnexpectedEof);
+    }
+    Ok(ChunkTable {
+        count: usize::try_from(count).map_err(|_| LotusError::UnexpectedEof)?,
+        // A chunk can be no longer than the whole stream, so a wider length is the same layout.
+        chunk_len: usize::try_from(chunk_len).unwrap_or(usize::MAX),
+        body,
+        offsets,
+    })
+}
+
+impl ChunkTable<'_> {
+    /// Decode chunk `index` into `out`, which must hold exactly that chunk's values.
+    fn decode_chunk(
+        &self,
+        index: usize,
+        out: &mut [u64],
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        let start = self.offsets[index];
+        let mut reader = BitReader::new(&self.body[start / 8..]);
+        reader.read_bits(start % 8)?;
+        for slot in out.iter_mut() {
+            *slot = lotus_read_u64(&mut reader, j_bits, tiers)?;
+        }
+        if start + reader.bits_consumed() - start % 8 != self.offsets[index + 1] {
+            return Err(LotusError::InvalidEncoding);
+        }
+        Ok(())
+    }
+}
+
+/// Encode `values` in chunks of `chunk_len`, prefixed by a chunk offset table.
+///
+/// Counts, the chunk length and the table use [`LOTUS_J3D1`](crate::LOTUS_J3D1); values use the
+/// caller's `(j_bits, tiers)`. Chunks follow each other with no padding in between.
+pub fn chunked_encode(
+    values: &[u64],
+    chunk_len: usize,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u8>, LotusError> {
+    if chunk_len == 0 {
+        return Err(LotusError::InvalidEncoding);
+    }
+    let chunks = values
+        .chunks(chunk_len)
+        .map(|chunk| encode_chunk(chunk, j_bits, tiers))
+        .collect::<Result<Vec<_>, _>>()?;
+    stitch(values.len(), chunk_len, &chunks)
+}
+
+/// Decode a buffer produced by [`chunked_encode`] or [`par_chunked_encode`].
+pub fn chunked_decode(bytes: &[u8], j_bits: usize, tiers: usize) -> Result<Vec<u64>, LotusError> {
+    let table = read_table(bytes)?;
+    let mut out = vec![0u64; table.count];
+    for (index, chunk) in out.chunks_mut(table.chunk_len).enumerate() {
+        table.decode_chunk(index, chunk, j_bits, tiers)?;
+    }
+    Ok(out)
+}
+
+/// Parallel counterpart of [`chunked_encode`]; the output is bit-identical.
+#[cfg(feature = "rayon")]
+pub fn par_chunked_encode(
+    values: &[u64],
+    chunk_len: usize,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u8>, LotusError> {
+    if chunk_len == 0 {
+        return Err(LotusError::InvalidEncoding);
+    }
+    let chunks = values
+        .par_chunks(chunk_len)
+        .map(|chunk| encode_chunk(chunk, j_bits, tiers))
+        .collect::<Result<Vec<_>, _>>()?;
+    stitch(values.len(), chunk_len, &chunks)
+}
+
+/// Parallel counterpart of [`chunked_decode`], decoding chunks concurrently via the offset table.
+#[cfg(feature = "rayon")]
+pub fn par_chunked_decode(
+    bytes: &[u8],
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u64>, LotusError> {
+    let table = read_table(bytes)?;
+    let mut out = vec![0u64; table.count];
+    out.par_chunks_mut(table.chunk_len)
+        .enumerate()
+        .try_for_each(|(index, chunk)| table.decode_chunk(index, chunk, j_bits, tiers))?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1};
+
+    fn sample(count: u64) -> Vec<u64> {
+        (0..count)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (i % 64))
+            .collect()
+    }
+
+    #[test]
+    fn round_trip_chunk_sizes() {
+        let values = sample(1_000);
+        for chunk_len in [1, 7, 64, 999, 1_000, 5_000] {
+            let encoded = chunked_encode(&values, chunk_len, LOTUS_J3D1.0, LOTUS_J3D1.1).unwrap();
+            let decoded = chunked_decode(&encoded, LOTUS_J3D1.0, LOTUS_J3D1.1).unwrap();
+            assert_eq!(decoded, values, "chunk_len = {chunk_len}");
+        }
+    }
+
+    #[test]
+    fn body_matches_unchunked_stream() {
+        let values: Vec<u64> = (0..300).collect();
+        let (j_bits, tiers) = LOTUS_J2D1;
+        let encoded = chunked_encode(&values, 32, j_bits, tiers).unwrap();
+        let plain = encode_chunk(&values, j_bits, tiers).unwrap().into_bytes();
+        assert!(encoded.ends_with(&plain));
+    }
+
+    #[test]
+    fn empty_input() {
+        let encoded = chunked_encode(&[], 16, 2, 1).unwrap();
+        assert!(chunked_decode(&encoded, 2, 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn corrupt_streams_rejected() {
+        let values = sample(40);
+        let encoded = chunked_encode(&values, 10, LOTUS_J3D1.0, LOTUS_J3D1.1).unwrap();
+        assert!(chunked_decode(&encoded, 3, 2).is_err());
+        assert!(chunked_decode(&encoded[..encoded.len() - 2], LOTUS_J3D1.0, LOTUS_J3D1.1).is_err());
+    }
+
+    #[test]
+    fn count_is_bounded_by_the_body() {
+        // One 8-bit chunk claiming 2^40 values must fail before 8 TiB is allocated for them.
+        let (hj, hd) = HEADER_CONFIG;
+        let mut header = BitWriter::new();
+        for field in [1 << 40, 1 << 40, 8] {
+            lotus_write_u64(&mut header, field, hj, hd).unwrap();
+        }
+        let mut bytes = header.into_bytes();
+        bytes.push(0xff);
+        assert_eq!(chunked_decode(&bytes, 2, 1), Err(LotusError::UnexpectedEof));
+        #[cfg(feature = "rayon")]
+        assert_eq!(
+            par_chunked_decode(&bytes, 2, 1),
+            Err(LotusError::UnexpectedEof)
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_is_bit_identical() {
+        let values = sample(100_000);
+        let (j_bits, tiers) = LOTUS_J3D1;
+        for chunk_len in [1, 333, 4_096] {
+            let serial = chunked_encode(&values, chunk_len, j_bits, tiers).unwrap();
+            let parallel = par_chunked_encode(&values, chunk_len, j_bits, tiers).unwrap();
+            assert_eq!(serial, parallel);
+            assert_eq!(par_chunked_decode(&serial, j_bits, tiers).unwrap(), values);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Length-delimited framing for `tokio-util` with Lotus-coded frame lengths.
+//!
+//! Each frame is a byte-aligned Lotus codeword holding the payload length, followed by the
+//! payload bytes.
+
+use std::io;
+
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{HEADER_CONFIG, LotusBufExt, LotusBufMutExt, LotusError, lotus_encoded_bits};
+
+/// Default upper bound on a frame's payload length: 8 MiB.
+const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// `tokio_util` codec framing payloads with a Lotus-coded length prefix.
+///
+/// A header split across reads is left in the buffer until the rest of it arrives. Frames longer
+/// than the maximum frame length are rejected on both the encode and decode side with
+/// [`io::ErrorKind::InvalidData`], before any payload is buffered.
+#[derive(Debug, Clone)]
+pub struct LotusLengthCodec {
+    j_bits: usize,
+    tiers: usize,
+    max_frame_length: usize,
+    /// Length of the frame whose header has been consumed but whose payload is still pending.
+    pending: Option<usize>,
+}
+
+impl LotusLengthCodec {
+    /// Codec using [`LOTUS_J3D1`](crate::LOTUS_J3D1) headers and an 8 MiB frame limit.
+    pub fn new() -> Self {
+        let (j_bits, tiers) = HEADER_CONFIG;
+        Self::with_config(j_bits, tiers)
+    }
+
+    pub fn with_config(j_bits: usize, tiers: usize) -> Self {
+        Self {
+            j_bits,
+            tiers,
+            max_frame_length: DEFAULT_MAX_FRAME_LENGTH,
+            pending: None,
+        }
+    }
+
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    pub fn set_max_frame_length(&mut self, max_frame_length: usize) {
+        self.max_frame_length = max_frame_length;
+    }
+
+    fn check_length(&self, length: u64) -> io::Result<usize> {
+        match usize::try_from(length) {
+            Ok(length) if length <= self.max_frame_length => Ok(length),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "frame of length {length} exceeds maximum of {}",
+                    self.max_frame_length
+                ),
+            )),
+        }
+    }
+}
+
+impl Default for LotusLengthCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for LotusLengthCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        let length = match self.pending {
+            Some(length) => length,
+            None => {
+                let mut header = &src[..];
+                let length = match header.get_lotus_u64(self.j_bits, self.tiers) {
+                    Ok(length) => self.check_length(length)?,
+                    Err(LotusError::UnexpectedEof) => return Ok(None),
+                    Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+                };
+                let header_len = src.len() - header.len();
+                let _ = src.split_to(header_len);
+                self.pending = Some(length);
+                length
+            }
+        };
+        if src.len() < length {
+            src.reserve(length - src.len());
+            return Ok(None);
+        }
+        self.pending = None;
+        Ok(Some(src.split_to(length)))
+    }
+}
+
+impl Encoder<Bytes> for LotusLengthCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> io::Result<()> {
+        let length = self.check_length(item.len() as u64)?;
+        let header_bits = lotus_encoded_bits(length as u64, self.j_bits, self.tiers)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        dst.reserve(header_bits.div_ceil(8) + length);
+        dst.put_lotus_u64(length as u64, self.j_bits, self.tiers)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        dst.extend_from_slice(&item);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LOTUS_J2D1;
+
+    #[test]
+    fn decodes_byte_by_byte() {
+        let mut codec = LotusLengthCodec::with_config(LOTUS_J2D1.0, LOTUS_J2D1.1);
+        let mut wire = BytesMut::new();
+        let payloads = [vec![], vec![7u8; 3], vec![1u8; 1_000]];
+        for payload in &payloads {
+            codec
+                .encode(Bytes::copy_from_slice(payload), &mut wire)
+                .unwrap();
+        }
+
+        let mut src = BytesMut::new();
+        let mut frames = Vec::new();
+        for &byte in wire.iter() {
+            src.extend_from_slice(&[byte]);
+            while let Some(frame) = codec.decode(&mut src).unwrap() {
+                frames.push(frame.to_vec());
+            }
+        }
+        assert_eq!(frames, payloads);
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn oversized_frames_rejected() {
+        let mut codec = LotusLengthCodec::new();
+        codec.set_max_frame_length(16);
+        let mut wire = BytesMut::new();
+        let err = codec
+            .encode(Bytes::from_static(&[0; 17]), &mut wire)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(wire.is_empty());
+
+        LotusLengthCodec::new()
+            .encode(Bytes::from_static(&[0; 17]), &mut wire)
+            .unwrap();
+        let err = codec.decode(&mut wire).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,649 @@
+//! The `.lotus` container: a checksummed, block-indexed file of Lotus-coded integers.
+//!
+//! Layout (version 1):
+//!
+//! * magic `LTUS` and a version byte;
+//! * header: `j_bits`, `tiers`, sequence codec tag and block length, each a byte-aligned
+//!   [`LOTUS_J3D1`](crate::LOTUS_J3D1) codeword;
+//! * blocks: up to `block_len` values each, at most [`MAX_BLOCK_LEN`], written with
+//!   [`sequence_write`] in the container's `(j_bits, tiers)` and padded to a whole byte;
+//! * footer: CRC32 of the magic, version and header, value count, block count, then each block's
+//!   byte offset, byte length and CRC32, packed as `LOTUS_J3D1` codewords;
+//! * trailer: footer length and footer CRC32 as little-endian `u32`, then the magic again.
+//!
+//! Every block is checked against its CRC32 when read, so corruption is reported as
+//! [`LotusError::ChecksumMismatch`] instead of decoding to wrong values.
+//! [`ContainerReader::verify`] goes further and locates the first corrupt codeword.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use crate::sequence::sequence_read_each;
+use crate::{
+    BitReader, BitWriter, HEADER_CONFIG, LotusError, ReadLotusExt, SequenceCodec, WriteLotusExt,
+    lotus_encoded_bits, lotus_read_u64, lotus_write_u64, sequence_read, sequence_write,
+    zigzag_encode,
+};
+
+const MAGIC: [u8; 4] = *b"LTUS";
+const VERSION: u8 = 1;
+/// Footer length, footer CRC32 and closing magic.
+const TRAILER_LEN: u64 = 12;
+/// Block length used by [`ContainerWriter::new`].
+pub const DEFAULT_BLOCK_LEN: usize = 4096;
+/// Largest block length a container may declare. It bounds what one block decodes to, however
+/// long the runs an RLE block claims.
+pub const MAX_BLOCK_LEN: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BlockEntry {
+    offset: u64,
+    len: u64,
+    crc: u32,
+}
+
+/// Streaming writer for `.lotus` containers.
+///
+/// Values are buffered until a block is full, then encoded, checksummed and written. Call
+/// [`finish`](Self::finish) to write the last block and the footer; a container that is dropped
+/// unfinished has no footer and is rejected by [`ContainerReader`]. After an error the writer
+/// should be discarded.
+#[derive(Debug)]
+pub struct ContainerWriter<W: Write> {
+    inner: W,
+    j_bits: usize,
+    tiers: usize,
+    codec: SequenceCodec,
+    block_len: usize,
+    pending: Vec<u64>,
+    /// Smallest and largest pending value, which bound a frame-of-reference block's residuals.
+    range: (u64, u64),
+    blocks: Vec<BlockEntry>,
+    position: u64,
+    count: u64,
+    header_crc: u32,
+}
+
+impl<W: Write> ContainerWriter<W> {
+    /// Start a container with [`DEFAULT_BLOCK_LEN`] values per block, writing its header.
+    pub fn new(
+        inner: W,
+        j_bits: usize,
+        tiers: usize,
+        codec: SequenceCodec,
+    ) -> Result<Self, LotusError> {
+        Self::with_block_len(inner, j_bits, tiers, codec, DEFAULT_BLOCK_LEN)
+    }
+
+    /// Start a container with `block_len` values per block, which must be in
+    /// `1..=MAX_BLOCK_LEN`.
+    pub fn with_block_len(
+        mut inner: W,
+        j_bits: usize,
+        tiers: usize,
+        codec: SequenceCodec,
+        block_len: usize,
+    ) -> Result<Self, LotusError> {
+        if !(1..=8).contains(&j_bits) || tiers == 0 || !(1..=MAX_BLOCK_LEN).contains(&block_len) {
+            return Err(LotusError::InvalidEncoding);
+        }
+        let (hj, hd) = HEADER_CONFIG;
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        for field in [j_bits as u64, tiers as u64, codec.tag(), block_len as u64] {
+            header.write_lotus_u64(field, hj, hd)?;
+        }
+        inner.write_all(&header)?;
+        Ok(Self {
+            inner,
+            j_bits,
+            tiers,
+            codec,
+            block_len,
+            pending: Vec::with_capacity(block_len),
+            range: (u64::MAX, 0),
+            blocks: Vec::new(),
+            position: header.len() as u64,
+            count: 0,
+            header_crc: crc32fast::hash(&header),
+        })
+    }
+
+    /// Append one value; values the configuration cannot code are rejected up front, leaving
+    /// the writer as it was.
+    pub fn push(&mut self, value: u64) -> Result<(), LotusError> {
+        let range = (self.range.0.min(value), self.range.1.max(value));
+        let codeword = match self.codec {
+            SequenceCodec::Plain | SequenceCodec::Rle => value,
+            SequenceCodec::Delta => {
+                let prev = self.pending.last().copied().unwrap_or(0);
+                zigzag_encode(value.wrapping_sub(prev) as i64)
+            }
+            // Every residual in the block is at most the widest spread from the minimum.
+            SequenceCodec::FrameOfReference => range.1 - range.0,
+        };
+        lotus_encoded_bits(codeword, self.j_bits, self.tiers)?;
+        self.pending.push(value);
+        self.range = range;
+        self.count += 1;
+        if self.pending.len() >= self.block_len {
+            self.flush_block()?;
+        }
+        Ok(())
+    }
+
+    pub fn extend(&mut self, values: &[u64]) -> Result<(), LotusError> {
+        values.iter().try_for_each(|&value| self.push(value))
+    }
+
+    fn flush_block(&mut self) -> Result<(), LotusError> {
+        let mut writer = BitWriter::new();
+        sequence_write(
+            &mut writer,
+            &self.pending,
+            self.codec,
+            self.j_bits,
+            self.tiers,
+        )?;
+        let bytes = writer.into_bytes();
+        self.inner.write_all(&bytes)?;
+        self.blocks.push(BlockEntry {
+            offset: self.position,
+            len: bytes.len() as u64,
+            crc: crc32fast::hash(&bytes),
+        });
+        self.position += bytes.len() as u64;
+        self.pending.clear();
+        self.range = (u64::MAX, 0);
+        Ok(())
+    }
+
+    /// Write the final block, footer and trailer, returning the inner writer.
+    pub fn finish(mut self) -> Result<W, LotusError> {
+        if !self.pending.is_empty() {
+            self.flush_block()?;
+        }
+        let (hj, hd) = HEADER_CONFIG;
+        let mut footer = BitWriter::new();
+        lotus_write_u64(&mut footer, self.header_crc as u64, hj, hd)?;
+        lotus_write_u64(&mut footer, self.count, hj, hd)?;
+        lotus_write_u64(&mut footer, self.blocks.len() as u64, hj, hd)?;
+        for block in &self.blocks {
+            lotus_write_u64(&mut footer, block.offset, hj, hd)?;
+            lotus_write_u64(&mut footer, block.len, hj, hd)?;
+            lotus_write_u64(&mut footer, block.crc as u64, hj, hd)?;
+        }
+        let footer = footer.into_bytes();
+        let footer_len = u32::try_from(footer.len()).map_err(|_| LotusError::ValueTooLarge)?;
+        self.inner.write_all(&footer)?;
+        self.inner.write_all(&footer_len.to_le_bytes())?;
+        self.inner
+            .write_all(&crc32fast::hash(&footer).to_le_bytes())?;
+        self.inner.write_all(&MAGIC)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Outcome of [`ContainerReader::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    /// Values before the first corruption, or every value.
+    pub values: u64,
+    /// Blocks that passed every check.
+    pub blocks: usize,
+    pub corruption: Option<Corruption>,
+}
+
+/// The first place a container fails verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corruption {
+    pub block: usize,
+    /// Container-wide index of the first value that cannot be trusted.
+    pub value_index: u64,
+    /// Bit offset from the start of the file of the codeword that failed to decode, or of the
+    /// block when only its checksum disagrees.
+    pub bit_offset: u64,
+    pub error: LotusError,
+}
+
+/// Random-access reader for `.lotus` containers.
+///
+/// Opening validates the header and the footer checksum; each block is checked against its
+/// CRC32 when it is read.
+#[derive(Debug)]
+pub struct ContainerReader<R: Read + Seek> {
+    inner: R,
+    j_bits: usize,
+    tiers: usize,
+    codec: SequenceCodec,
+    block_len: usize,
+    count: u64,
+    blocks: Vec<BlockEntry>,
+}
+
+impl<R: Read + Seek> ContainerReader<R> {
+    pub fn new(mut inner: R) -> Result<Self, LotusError> {
+        let file_len = inner.seek(SeekFrom::End(0))?;
+        inner.seek(SeekFrom::Start(0))?;
+        let mut magic = [0u8; 5];
+        inner.read_exact(&mut magic)?;
+        if magic[..4] != MAGIC {
+            return Err(LotusError::InvalidEncoding);
+        }
+        if magic[4] != VERSION {
+            return Err(LotusError::Message(format!(
+                "unsupported container version {}",
+                magic[4]
+            )));
+        }
+        let (hj, hd) = HEADER_CONFIG;
+        let mut field = || inner.read_lotus_u64(hj, hd).map_err(LotusError::from);
+        let j_bits = field()?;
+        let tiers = field()?;
+        let codec = SequenceCodec::from_tag(field()?)?;
+        let block_len = field()?;
+        if !(1..=8).contains(&j_bits)
+            || !(1..=64).contains(&tiers)
+            || !(1..=MAX_BLOCK_LEN as u64).contains(&block_len)
+        {
+            return Err(LotusError::InvalidEncoding);
+        }
+        let block_len = block_len as usize;
+        let data_start = inner.stream_position()?;
+
+        if file_len < data_start + TRAILER_LEN {
+            return Err(LotusError::UnexpectedEof);
+        }
+        inner.seek(SeekFrom::Start(file_len - TRAILER_LEN))?;
+        let mut trailer = [0u8; TRAILER_LEN as usize];
+        inner.read_exact(&mut trailer)?;
+        if trailer[8..] != MAGIC {
+            return Err(LotusError::InvalidEncoding);
+        }
+        let footer_len = u32::from_le_bytes(trailer[..4].try_into().unwrap()) as u64;
+        let footer_crc = u32::from_le_bytes(trailer[4..8].try_into().unwrap());
+        let footer_start = (file_len - TRAILER_LEN)
+            .checked_sub(footer_len)
+            .filter(|&start| start >= data_start)
+            .ok_or(LotusError::InvalidEncoding)?;
+        inner.seek(SeekFrom::Start(footer_start))?;
+        let mut footer = vec![0u8; footer_len as usize];
+        inner.read_exact(&mut footer)?;
+        if crc32fast::hash(&footer) != footer_crc {
+            return Err(LotusError::ChecksumMismatch);
+        }
+
+        let mut reader = BitReader::new(&footer);
+        let header_crc = lotus_read_u64(&mut reader, hj, hd)?;
+        inner.seek(SeekFrom::Start(0))?;
+        let mut header = vec![0u8; data_start as usize];
+        inner.read_exact(&mut header)?;
+        if crc32fast::hash(&header) as u64 != header_crc {
+            return Err(LotusError::ChecksumMismatch);
+        }
+        let count = lotus_read_u64(&mut reader, hj, hd)?;
+        let block_count = lotus_read_u64(&mut reader, hj, hd)?;
+        if block_count != count.div_ceil(block_len as u64) {
+            return Err(LotusError::InvalidEncoding);
+        }
+        // Each entry takes at least three bits.
+        if block_count > (reader.bits_remaining() / 3) as u64 {
+            return Err(LotusError::UnexpectedEof);
+        }
+        let mut blocks = Vec::with_capacity(block_count as usize);
+        for _ in 0..block_count {
+            let offset = lotus_read_u64(&mut reader, hj, hd)?;
+            let len = lotus_read_u64(&mut reader, hj, hd)?;
+            let crc = u32::try_from(lotus_read_u64(&mut reader, hj, hd)?)
+                .map_err(|_| LotusError::InvalidEncoding)?;
+            let in_bounds = offset >= data_start
+                && offset
+                    .checked_add(len)
+                    .is_some_and(|end| end <= footer_start);
+            if !in_bounds {
+                return Err(LotusError::InvalidEncoding);
+            }
+            blocks.push(BlockEntry { offset, len, crc });
+        }
+        Ok(Self {
+            inner,
+            j_bits: j_bits as usize,
+            tiers: tiers as usize,
+            codec,
+            block_len,
+            count,
+            blocks,
+        })
+    }
+
+    pub fn j_bits(&self) -> usize {
+        self.j_bits
+    }
+
+    pub fn tiers(&self) -> usize {
+        self.tiers
+    }
+
+    pub fn codec(&self) -> SequenceCodec {
+        self.codec
+    }
+
+    pub fn block_len(&self) -> usize {
+        self.block_len
+    }
+
+    /// Total number of values in the container.
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Index of the first value in block `index`.
+    fn block_start(&self, index: usize) -> u64 {
+        index as u64 * self.block_len as u64
+    }
+
+    fn block_values(&self, index: usize) -> usize {
+        (self.count - self.block_start(index)).min(self.block_len as u64) as usize
+    }
+
+    fn block_bytes(&mut self, block: BlockEntry) -> Result<Vec<u8>, LotusError> {
+        self.inner.seek(SeekFrom::Start(block.offset))?;
+        let mut bytes = vec![0u8; block.len as usize];
+        self.inner.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Read and verify block `index`, returning its values.
+    ///
+    /// A block decodes to at most [`block_len`](Self::block_len) values, so no block yields more
+    /// than [`MAX_BLOCK_LEN`]; runs that would overfill it are [`LotusError::InvalidEncoding`].
+    pub fn read_block(&mut self, index: usize) -> Result<Vec<u64>, LotusError> {
+        let block = *self.blocks.get(index).ok_or(LotusError::InvalidEncoding)?;
+        let values = self.block_values(index);
+        let bytes = self.block_bytes(block)?;
+        if crc32fast::hash(&bytes) != block.crc {
+            return Err(LotusError::ChecksumMismatch);
+        }
+        let mut reader = BitReader::new(&bytes);
+        let decoded = sequence_read(&mut reader, values, self.codec, self.j_bits, self.tiers)?;
+        if reader.bits_consumed().div_ceil(8) != bytes.len() {
+            return Err(LotusError::InvalidEncoding);
+        }
+        Ok(decoded)
+    }
+
+    /// Read and verify every block in order.
+    pub fn read_all(&mut self) -> Result<Vec<u64>, LotusError> {
+        let mut values = Vec::with_capacity(self.count.min(1 << 20) as usize);
+        for index in 0..self.blocks.len() {
+            values.extend(self.read_block(index)?);
+        }
+        Ok(values)
+    }
+
+    /// Decode every block under strict rules and report the first corruption.
+    ///
+    /// Beyond what [`read_block`](Self::read_block) checks, each block must decode to exactly its
+    /// share of the value count, end in its last byte and pad with zero bits. Blocks are decoded
+    /// even when their checksum disagrees, so a bad codeword is located to the bit. Only I/O
+    /// failures are returned as errors.
+    pub fn verify(&mut self) -> Result<Verification, LotusError> {
+        let mut verified = 0u64;
+        for index in 0..self.blocks.len() {
+            let block = self.blocks[index];
+            let bytes = self.block_bytes(block)?;
+            let mut reader = BitReader::new(&bytes);
+            let (mut decoded, mut clean_bits) = (0u64, 0usize);
+            let decode = sequence_read_each(
+                &mut reader,
+                self.block_values(index),
+                self.codec,
+                self.j_bits,
+                self.tiers,
+                |_, end| {
+                    decoded += 1;
+                    clean_bits = end;
+                },
+            )
+            .and_then(|()| {
+                clean_bits = reader.bits_consumed();
+                check_padding(&mut reader)
+            });
+            let failure = match decode {
+                Err(error) => Some((verified + decoded, clean_bits, error)),
+                Ok(()) if crc32fast::hash(&bytes) != block.crc => {
+                    Some((self.block_start(index), 0, LotusError::ChecksumMismatch))
+                }
+                Ok(()) => None,
+            };
+            if let Some((value_index, bits, error)) = failure {
+                return Ok(Verification {
+                    values: value_index,
+                    blocks: index,
+                    corruption: Some(Corruption {
+                        block: index,
+                        value_index,
+                        bit_offset: block.offset * 8 + bits as u64,
+                        error,
+                    }),
+                });
+            }
+            verified += decoded;
+        }
+        Ok(Verification {
+            values: verified,
+            blocks: self.blocks.len(),
+            corruption: None,
+        })
+    }
+}
+
+/// A block must end within its last byte, and the bits that pad that byte must be zero.
+fn check_padding(reader: &mut BitReader<'_>) -> Result<(), LotusError> {
+    let padding = reader.bits_remaining();
+    if padding >= 8 || reader.read_bits(padding)? != 0 {
+        return Err(LotusError::InvalidEncoding);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1};
+    use std::io::Cursor;
+
+    fn sample(count: u64) -> Vec<u64> {
+        (0..count).map(|i| (i * i) % 5_000 + i / 7).collect()
+    }
+
+    fn build(values: &[u64], codec: SequenceCodec, block_len: usize) -> Vec<u8> {
+        let (j_bits, tiers) = LOTUS_J2D1;
+        let mut writer =
+            ContainerWriter::with_block_len(Vec::new(), j_bits, tiers, codec, block_len).unwrap();
+        writer.extend(values).unwrap();
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn round_trip_codecs_and_block_sizes() {
+        let values = sample(1_000);
+        for codec in [
+            SequenceCodec::Plain,
+            SequenceCodec::Delta,
+            SequenceCodec::FrameOfReference,
+            SequenceCodec::Rle,
+        ] {
+            for block_len in [1, 100, 999, 1_000, 4_096] {
+                let bytes = build(&values, codec, block_len);
+                let mut reader = ContainerReader::new(Cursor::new(bytes)).unwrap();
+                assert_eq!(reader.codec(), codec);
+                assert_eq!(reader.len(), 1_000);
+                assert_eq!(reader.block_count(), 1_000usize.div_ceil(block_len));
+                assert_eq!(reader.read_all().unwrap(), values, "{codec:?}/{block_len}");
+            }
+        }
+    }
+
+    #[test]
+    fn empty_container_and_random_access() {
+        let bytes = build(&[], SequenceCodec::Plain, 16);
+        let mut reader = ContainerReader::new(Cursor::new(bytes)).unwrap();
+        assert!(reader.is_empty());
+        assert!(reader.read_all().unwrap().is_empty());
+
+        let values = sample(50);
+        let mut reader =
+            ContainerReader::new(Cursor::new(build(&values, SequenceCodec::Delta, 16))).unwrap();
+        assert_eq!(reader.read_block(3).unwrap(), values[48..]);
+        assert_eq!(reader.read_block(1).unwrap(), values[16..32]);
+        assert!(reader.read_block(4).is_err());
+    }
+
+    #[test]
+    fn rejects_unencodable_values_and_bad_config() {
+        let mut writer = ContainerWriter::new(Vec::new(), 2, 1, SequenceCodec::Plain).unwrap();
+        assert_eq!(writer.push(u64::MAX), Err(LotusError::ValueTooLarge));
+        assert!(ContainerWriter::new(Vec::new(), 0, 1, SequenceCodec::Plain).is_err());
+        let wide = ContainerWriter::new(Vec::new(), LOTUS_J3D1.0, LOTUS_J3D1.1, SequenceCodec::Rle);
+        assert!(wide.unwrap().push(u64::MAX).is_ok());
+
+        // A rejected delta or residual leaves the block as it was, so it still flushes on time.
+        let (j_bits, tiers) = (1, 1);
+        for codec in [SequenceCodec::Delta, SequenceCodec::FrameOfReference] {
+            let mut writer =
+                ContainerWriter::with_block_len(Vec::new(), j_bits, tiers, codec, 4).unwrap();
+            writer.extend(&[1, 1, 1]).unwrap();
+            assert_eq!(writer.push(100_000), Err(LotusError::ValueTooLarge));
+            writer.extend(&[1; 5]).unwrap();
+            let mut reader = ContainerReader::new(Cursor::new(writer.finish().unwrap())).unwrap();
+            assert_eq!(reader.block_count(), 2, "{codec:?}");
+            assert_eq!(reader.read_all().unwrap(), [1; 8]);
+        }
+    }
+
+    /// One RLE block of `value` repeated `run + 1` times, written by hand so the header and footer
+    /// can claim anything.
+    fn raw_rle(block_len: u64, count: u64, run: u64) -> Vec<u8> {
+        let (hj, hd) = HEADER_CONFIG;
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        for field in [2, 1, SequenceCodec::Rle.tag(), block_len] {
+            header.write_lotus_u64(field, hj, hd).unwrap();
+        }
+        let mut block = BitWriter::new();
+        lotus_write_u64(&mut block, 1, LOTUS_J2D1.0, LOTUS_J2D1.1).unwrap();
+        lotus_write_u64(&mut block, run, hj, hd).unwrap();
+        let block = block.into_bytes();
+        let mut footer = BitWriter::new();
+        for field in [
+            crc32fast::hash(&header) as u64,
+            count,
+            1,
+            header.len() as u64,
+            block.len() as u64,
+            crc32fast::hash(&block) as u64,
+        ] {
+            lotus_write_u64(&mut footer, field, hj, hd).unwrap();
+        }
+        let footer = footer.into_bytes();
+        let mut bytes = [header, block, footer.clone()].concat();
+        bytes.extend_from_slice(&(footer.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&crc32fast::hash(&footer).to_le_bytes());
+        bytes.extend_from_slice(&MAGIC);
+        bytes
+    }
+
+    #[test]
+    fn block_length_bounds_rle_expansion() {
+        // Two bytes claiming a run of 2^60 values are refused when the container is opened.
+        let bomb = raw_rle(1 << 60, 1 << 60, (1 << 60) - 1);
+        assert_eq!(
+            ContainerReader::new(Cursor::new(bomb)).unwrap_err(),
+            LotusError::InvalidEncoding
+        );
+
+        let mut reader = ContainerReader::new(Cursor::new(raw_rle(8, 8, 7))).unwrap();
+        assert_eq!(reader.read_all().unwrap(), [1; 8]);
+        // A run one longer than its block is rejected before it expands.
+        let mut reader = ContainerReader::new(Cursor::new(raw_rle(8, 8, 8))).unwrap();
+        assert_eq!(reader.read_block(0), Err(LotusError::InvalidEncoding));
+        let corruption = reader.verify().unwrap().corruption.unwrap();
+        assert_eq!(corruption.error, LotusError::InvalidEncoding);
+
+        let (j_bits, tiers) = LOTUS_J2D1;
+        for block_len in [0, MAX_BLOCK_LEN + 1, usize::MAX] {
+            let writer = ContainerWriter::with_block_len(
+                Vec::new(),
+                j_bits,
+                tiers,
+                SequenceCodec::Rle,
+                block_len,
+            );
+            assert_eq!(writer.unwrap_err(), LotusError::InvalidEncoding);
+        }
+        assert!(
+            ContainerWriter::with_block_len(
+                Vec::new(),
+                j_bits,
+                tiers,
+                SequenceCodec::Rle,
+                MAX_BLOCK_LEN
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn verify_locates_corruption() {
+        let values = sample(100);
+        let bytes = build(&values, SequenceCodec::Plain, 16);
+        let mut reader = ContainerReader::new(Cursor::new(bytes.clone())).unwrap();
+        let verified = reader.verify().unwrap();
+        assert_eq!((verified.values, verified.blocks), (100, 7));
+        assert_eq!(verified.corruption, None);
+        let blocks = reader.blocks.clone();
+
+        // A zero byte cannot start a J2D1 codeword, so block 2 fails at its first value.
+        let mut zeroed = bytes.clone();
+        zeroed[blocks[2].offset as usize] = 0;
+        let verified = ContainerReader::new(Cursor::new(zeroed))
+            .unwrap()
+            .verify()
+            .unwrap();
+        let corruption = verified.corruption.unwrap();
+        assert_eq!(verified.values, 32);
+        assert_eq!((corruption.block, corruption.value_index), (2, 32));
+        assert_eq!(corruption.bit_offset, blocks[2].offset * 8);
+        assert_eq!(corruption.error, LotusError::InvalidEncoding);
+
+        for codec in [
+            SequenceCodec::Delta,
+            SequenceCodec::FrameOfReference,
+            SequenceCodec::Rle,
+        ] {
+            let bytes = build(&values, codec, 16);
+            let blocks = ContainerReader::new(Cursor::new(bytes.clone()))
+                .unwrap()
+                .blocks;
+            for (index, block) in blocks.iter().enumerate() {
+                let mut flipped = bytes.clone();
+                flipped[(block.offset + block.len - 1) as usize] ^= 0x01;
+                let mut reader = ContainerReader::new(Cursor::new(flipped)).unwrap();
+                let corruption = reader.verify().unwrap().corruption.unwrap();
+                assert_eq!(corruption.block, index, "{codec:?}");
+                assert!(corruption.value_index >= index as u64 * 16);
+                assert!(corruption.bit_offset >= block.offset * 8);
+                assert!(corruption.bit_offset < (block.offset + block.len) * 8);
+            }
+        }
+    }
+}
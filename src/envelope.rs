@@ -0,0 +1,105 @@
+//! The size envelope of a configuration: codeword length as a function of the value.
+
+use crate::{LotusError, lotus_chain};
+
+/// Every value whose payload has one particular width, and the codeword shape they share.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeRow {
+    pub payload_width: usize,
+    /// Smallest value with this payload width.
+    pub min_value: u64,
+    /// Largest value with this payload width, capped at `u64::MAX`.
+    pub max_value: u64,
+    /// Tier field widths in stream order, outermost first.
+    pub tier_widths: Vec<usize>,
+    /// Total codeword length: jumpstarter, tier fields and payload.
+    pub codeword_bits: usize,
+}
+
+/// Smallest value whose payload is `width` bits wide.
+///
+/// Width `w` covers `v + 1` in `2^w - 2 ..= 2^(w+1) - 3`; width 1 therefore only holds 0.
+fn width_start(width: usize) -> Option<u64> {
+    let start = 1u128.checked_shl(width as u32)?.saturating_sub(3);
+    u64::try_from(start).ok()
+}
+
+/// One row per payload width the configuration can express, in increasing order.
+///
+/// Invalid configurations are [`LotusError::InvalidEncoding`], as for the codec itself.
+pub fn lotus_envelope(j_bits: usize, tiers: usize) -> Result<Vec<EnvelopeRow>, LotusError> {
+    let mut rows = Vec::new();
+    for payload_width in 1.. {
+        let Some(min_value) = width_start(payload_width) else {
+            break;
+        };
+        let chain = match lotus_chain(min_value, j_bits, tiers) {
+            Ok((_, chain)) => chain,
+            Err(LotusError::ValueTooLarge | LotusError::JumpstarterOverflow) => break,
+            Err(err) => return Err(err),
+        };
+        let max_value = width_start(payload_width + 1).map_or(u64::MAX, |next| next - 1);
+        rows.push(EnvelopeRow {
+            payload_width,
+            min_value,
+            max_value,
+            tier_widths: chain[1..].iter().rev().map(|&(_, width)| width).collect(),
+            codeword_bits: j_bits + chain.iter().map(|&(_, width)| width).sum::<usize>(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Largest value the configuration can encode.
+pub fn lotus_max_value(j_bits: usize, tiers: usize) -> Result<u64, LotusError> {
+    lotus_envelope(j_bits, tiers)?
+        .last()
+        .map(|row| row.max_value)
+        .ok_or(LotusError::ValueTooLarge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1, lotus_encode_u64, lotus_encoded_bits};
+
+    #[test]
+    fn rows_match_the_codec() {
+        for (j_bits, tiers) in [LOTUS_J2D1, LOTUS_J3D1, (1, 1), (1, 2), (4, 2)] {
+            let rows = lotus_envelope(j_bits, tiers).unwrap();
+            assert_eq!(rows[0].min_value, 0);
+            for pair in rows.windows(2) {
+                assert_eq!(pair[0].max_value + 1, pair[1].min_value);
+                assert!(pair[0].codeword_bits <= pair[1].codeword_bits);
+            }
+            for row in &rows {
+                assert_eq!(row.tier_widths.len(), tiers);
+                for value in [row.min_value, row.max_value] {
+                    assert_eq!(
+                        lotus_encoded_bits(value, j_bits, tiers).unwrap(),
+                        row.codeword_bits,
+                        "J{j_bits}D{tiers} value {value}"
+                    );
+                }
+            }
+            let max = lotus_max_value(j_bits, tiers).unwrap();
+            assert_eq!(max, rows.last().unwrap().max_value);
+            if max < u64::MAX {
+                assert!(lotus_encode_u64(max + 1, j_bits, tiers).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn known_shapes() {
+        let rows = lotus_envelope(2, 1).unwrap();
+        // Width 1 holds only 0 and width 2 holds 1..=4, both behind a 2-bit tier field.
+        assert_eq!((rows[0].min_value, rows[0].max_value), (0, 0));
+        assert_eq!((rows[1].min_value, rows[1].max_value), (1, 4));
+        assert_eq!(rows[1].tier_widths, [2]);
+        assert_eq!(rows[1].codeword_bits, 2 + 2 + 2);
+        assert_eq!(lotus_max_value(3, 1).unwrap(), u64::MAX);
+        assert_eq!(lotus_envelope(0, 1), Err(LotusError::InvalidEncoding));
+        assert_eq!(lotus_envelope(2, 0), Err(LotusError::InvalidEncoding));
+    }
+}
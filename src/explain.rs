@@ -0,0 +1,213 @@
+//! Field-by-field breakdown of a single codeword, for debugging and teaching.
+
+use crate::{BitReader, LotusError, lotus_chain, lotus_decode_value, max_width_for_config};
+
+/// One field of a codeword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodewordField {
+    /// Bit offset from the start of the codeword.
+    pub offset: usize,
+    /// Width of the field in bits.
+    pub width: usize,
+    /// Raw contents, most significant bit first.
+    pub bits: u64,
+    /// What the field means: the width of the next field for the jumpstarter and tiers, the
+    /// encoded value for the payload.
+    pub decoded: u64,
+}
+
+/// A codeword split into its jumpstarter, tier fields and payload, in stream order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Codeword {
+    pub j_bits: usize,
+    pub tiers: usize,
+    pub jumpstarter: CodewordField,
+    /// Tier fields from outermost (sized by the jumpstarter) to innermost (sizing the payload).
+    pub tier_fields: Vec<CodewordField>,
+    pub payload: CodewordField,
+}
+
+impl Codeword {
+    /// Break down the codeword [`lotus_write_u64`](crate::lotus_write_u64) would emit for `value`.
+    pub fn encode(value: u64, j_bits: usize, tiers: usize) -> Result<Self, LotusError> {
+        let (jump_val, chain) = lotus_chain(value, j_bits, tiers)?;
+        let jumpstarter = CodewordField {
+            offset: 0,
+            width: j_bits,
+            bits: jump_val,
+            decoded: jump_val + 1,
+        };
+        let mut offset = j_bits;
+        let mut fields: Vec<CodewordField> = chain
+            .iter()
+            .rev()
+            .map(|&(bits, width)| {
+                let field = CodewordField {
+                    offset,
+                    width,
+                    bits,
+                    decoded: 0,
+                };
+                offset += width;
+                field
+            })
+            .collect();
+        // Each field decodes to the width of the one after it; the payload to the value.
+        for index in 0..fields.len() - 1 {
+            fields[index].decoded = fields[index + 1].width as u64;
+        }
+        let mut payload = fields.pop().ok_or(LotusError::InvalidEncoding)?;
+        payload.decoded = value;
+        Ok(Self {
+            j_bits,
+            tiers,
+            jumpstarter,
+            tier_fields: fields,
+            payload,
+        })
+    }
+
+    /// Read and break down the next codeword from `reader`, with the same checks as
+    /// [`lotus_read_u64`](crate::lotus_read_u64). Offsets are relative to the reader's position.
+    pub fn read(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        if !(1..=8).contains(&j_bits) || tiers == 0 {
+            return Err(LotusError::InvalidEncoding);
+        }
+        let max_width = max_width_for_config(j_bits, tiers);
+        let jump_val = reader.read_bits(j_bits)?;
+        let jumpstarter = CodewordField {
+            offset: 0,
+            width: j_bits,
+            bits: jump_val,
+            decoded: jump_val + 1,
+        };
+        let mut next_width = jump_val as usize + 1;
+        if next_width as u128 > max_width {
+            return Err(LotusError::ValueTooLarge);
+        }
+
+        let mut offset = j_bits;
+        let mut tier_fields = Vec::with_capacity(tiers);
+        for _ in 0..tiers {
+            let bits = reader.read_bits(next_width)?;
+            let width_value = lotus_decode_value(bits, next_width)?;
+            if width_value == 0 || width_value as u128 > max_width {
+                return Err(LotusError::ValueTooLarge);
+            }
+            tier_fields.push(CodewordField {
+                offset,
+                width: next_width,
+                bits,
+                decoded: width_value,
+            });
+            offset += next_width;
+            next_width = width_value as usize;
+        }
+
+        let bits = reader.read_bits(next_width)?;
+        let payload = CodewordField {
+            offset,
+            width: next_width,
+            bits,
+            decoded: lotus_decode_value(bits, next_width)?,
+        };
+        Ok(Self {
+            j_bits,
+            tiers,
+            jumpstarter,
+            tier_fields,
+            payload,
+        })
+    }
+
+    /// Break down the codeword at the start of `bytes`.
+    pub fn decode(bytes: &[u8], j_bits: usize, tiers: usize) -> Result<Self, LotusError> {
+        Self::read(&mut BitReader::new(bytes), j_bits, tiers)
+    }
+
+    /// The encoded value.
+    pub fn value(&self) -> u64 {
+        self.payload.decoded
+    }
+
+    /// Total length in bits, before byte padding.
+    pub fn bit_len(&self) -> usize {
+        self.payload.offset + self.payload.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitWriter, LOTUS_J2D1, LOTUS_J3D1, lotus_encode_u64, lotus_encoded_bits};
+
+    #[test]
+    fn encode_and_decode_agree() {
+        for (j_bits, tiers) in [LOTUS_J2D1, LOTUS_J3D1, (3, 2), (1, 3)] {
+            for value in [0u64, 1, 2, 42, 1_000, 1 << 20, 123_456_789] {
+                let Ok(expected) = Codeword::encode(value, j_bits, tiers) else {
+                    continue;
+                };
+                let bytes = lotus_encode_u64(value, j_bits, tiers).unwrap();
+                let decoded = Codeword::decode(&bytes, j_bits, tiers).unwrap();
+                assert_eq!(decoded, expected);
+                assert_eq!(decoded.value(), value);
+                assert_eq!(decoded.tier_fields.len(), tiers);
+                assert_eq!(
+                    decoded.bit_len(),
+                    lotus_encoded_bits(value, j_bits, tiers).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fields_reassemble_the_codeword() {
+        let (j_bits, tiers) = (3, 2);
+        let codeword = Codeword::encode(42, j_bits, tiers).unwrap();
+        let mut writer = BitWriter::new();
+        let fields = std::iter::once(&codeword.jumpstarter)
+            .chain(&codeword.tier_fields)
+            .chain(std::iter::once(&codeword.payload));
+        let mut expected_offset = 0;
+        for field in fields {
+            assert_eq!(field.offset, expected_offset);
+            writer.write_bits(field.bits, field.width).unwrap();
+            expected_offset += field.width;
+        }
+        assert_eq!(
+            writer.into_bytes(),
+            lotus_encode_u64(42, j_bits, tiers).unwrap()
+        );
+        assert_eq!(
+            codeword.jumpstarter.decoded as usize,
+            codeword.tier_fields[0].width
+        );
+        assert_eq!(
+            codeword.tier_fields[1].decoded as usize,
+            codeword.payload.width
+        );
+    }
+
+    #[test]
+    fn read_offsets_are_relative() {
+        let (j_bits, tiers) = LOTUS_J2D1;
+        let mut writer = BitWriter::new();
+        writer.write_bits(0b101, 3).unwrap();
+        crate::lotus_write_u64(&mut writer, 77, j_bits, tiers).unwrap();
+        let bytes = writer.into_bytes();
+        let mut reader = BitReader::new(&bytes);
+        reader.read_bits(3).unwrap();
+        let codeword = Codeword::read(&mut reader, j_bits, tiers).unwrap();
+        assert_eq!(codeword, Codeword::encode(77, j_bits, tiers).unwrap());
+        assert_eq!(reader.bits_consumed(), 3 + codeword.bit_len());
+        assert_eq!(
+            Codeword::decode(&[0x00], j_bits, tiers),
+            Err(LotusError::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Gorilla-style `f64` series compression with Lotus-coded control and window fields.
+//!
+//! Each value is XORed with its predecessor. A record starts with a Lotus-coded control code:
+//! the XOR is zero, fits the previous meaningful-bit window, opens a new window (followed by the
+//! Lotus-coded leading-zero count and meaningful length), or marks the end of the series.
+//! Values travel as raw IEEE-754 bits, so NaN payloads, signed zeros and infinities survive
+//! unchanged.
+
+use crate::{
+    BitReader, BitWriter, LotusError, lotus_encoded_bits, lotus_read_u64, lotus_write_u64,
+};
+
+const CONTROL_REPEAT: u64 = 0;
+const CONTROL_REUSE_WINDOW: u64 = 1;
+const CONTROL_NEW_WINDOW: u64 = 2;
+const CONTROL_END: u64 = 3;
+
+/// Meaningful-bit window of the last stored XOR: leading zeros and length.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    leading: u32,
+    len: u32,
+}
+
+impl Window {
+    fn trailing(self) -> u32 {
+        64 - self.leading - self.len
+    }
+}
+
+/// Streaming encoder for a series of `f64` values.
+#[derive(Debug, Clone)]
+pub struct F64SeriesEncoder {
+    writer: BitWriter,
+    j_bits: usize,
+    tiers: usize,
+    prev: u64,
+    window: Option<Window>,
+}
+
+impl F64SeriesEncoder {
+    /// Create an encoder; the `(j_bits, tiers)` configuration must be able to code the value 63.
+    pub fn new(j_bits: usize, tiers: usize) -> Result<Self, LotusError> {
+        lotus_encoded_bits(63, j_bits, tiers)?;
+        Ok(Self {
+            writer: BitWriter::new(),
+            j_bits,
+            tiers,
+            prev: 0,
+            window: None,
+        })
+    }
+
+    pub fn push(&mut self, value: f64) -> Result<(), LotusError> {
+        let bits = value.to_bits();
+        let xor = bits ^ self.prev;
+        self.prev = bits;
+        if xor == 0 {
+            return self.control(CONTROL_REPEAT);
+        }
+        let leading = xor.leading_zeros();
+        let trailing = xor.trailing_zeros();
+        match self.window {
+            Some(window) if leading >= window.leading && trailing >= window.trailing() => {
+                self.control(CONTROL_REUSE_WINDOW)?;
+                self.writer
+                    .write_bits(xor >> window.trailing(), window.len as usize)
+            }
+            _ => {
+                let window = Window {
+                    leading,
+                    len: 64 - leading - trailing,
+                };
+                self.control(CONTROL_NEW_WINDOW)?;
+                lotus_write_u64(
+                    &mut self.writer,
+                    window.leading as u64,
+                    self.j_bits,
+                    self.tiers,
+                )?;
+                lotus_write_u64(
+                    &mut self.writer,
+                    (window.len - 1) as u64,
+                    self.j_bits,
+                    self.tiers,
+                )?;
+                self.writer
+                    .write_bits(xor >> trailing, window.len as usize)?;
+                self.window = Some(window);
+                Ok(())
+            }
+        }
+    }
+
+    /// Write the end marker and return the packed series.
+    pub fn finish(mut self) -> Result<Vec<u8>, LotusError> {
+        self.control(CONTROL_END)?;
+        Ok(self.writer.into_bytes())
+    }
+
+    fn control(&mut self, code: u64) -> Result<(), LotusError> {
+        lotus_write_u64(&mut self.writer, code, self.j_bits, self.tiers)
+    }
+}
+
+/// Streaming decoder for series produced by [`F64SeriesEncoder`].
+#[derive(Debug, Clone)]
+pub struct F64SeriesDecoder<'a> {
+    reader: BitReader<'a>,
+    j_bits: usize,
+    tiers: usize,
+    prev: u64,
+    window: Option<Window>,
+    done: bool,
+}
+
+impl<'a> F64SeriesDecoder<'a> {
+    pub fn new(bytes: &'a [u8], j_bits: usize, tiers: usize) -> Self {
+        Self {
+            reader: BitReader::new(bytes),
+            j_bits,
+            tiers,
+            prev: 0,
+            window: None,
+            done: false,
+        }
+    }
+
+    /// Decode the next value, or `None` once the end marker has been read.
+    pub fn next_value(&mut self) -> Result<Option<f64>, LotusError> {
+        if self.done {
+            return Ok(None);
+        }
+        let xor = match lotus_read_u64(&mut self.reader, self.j_bits, self.tiers)? {
+            CONTROL_REPEAT => 0,
+            CONTROL_REUSE_WINDOW => {
+                let window = self.window.ok_or(LotusError::InvalidEncoding)?;
+                self.reader.read_bits(window.len as usize)? << window.trailing()
+            }
+            CONTROL_NEW_WINDOW => {
+                let leading = lotus_read_u64(&mut self.reader, self.j_bits, self.tiers)?;
+                let len = lotus_read_u64(&mut self.reader, self.j_bits, self.tiers)?;
+                if leading >= 64 || len >= 64 - leading {
+                    return Err(LotusError::InvalidEncoding);
+                }
+                let window = Window {
+                    leading: leading as u32,
+                    len: len as u32 + 1,
+                };
+                self.window = Some(window);
+                self.reader.read_bits(window.len as usize)? << window.trailing()
+            }
+            CONTROL_END => {
+                self.done = true;
+                return Ok(None);
+            }
+            _ => return Err(LotusError::InvalidEncoding),
+        };
+        self.prev ^= xor;
+        Ok(Some(f64::from_bits(self.prev)))
+    }
+}
+
+impl Iterator for F64SeriesDecoder<'_> {
+    type Item = Result<f64, LotusError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.next_value().transpose();
+        if matches!(item, Some(Err(_))) {
+            self.done = true;
+        }
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1};
+
+    fn round_trip(values: &[f64], cfg: (usize, usize)) -> Vec<u8> {
+        let mut encoder = F64SeriesEncoder::new(cfg.0, cfg.1).unwrap();
+        for &value in values {
+            encoder.push(value).unwrap();
+        }
+        let bytes = encoder.finish().unwrap();
+        let decoded: Vec<f64> = F64SeriesDecoder::new(&bytes, cfg.0, cfg.1)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let expected: Vec<u64> = values.iter().map(|v| v.to_bits()).collect();
+        let actual: Vec<u64> = decoded.iter().map(|v| v.to_bits()).collect();
+        assert_eq!(actual, expected);
+        bytes
+    }
+
+    #[test]
+    fn special_values_are_bit_exact() {
+        let quiet_payload = f64::from_bits(0x7ff8_0000_dead_beef);
+        let signalling = f64::from_bits(0x7ff0_0000_0000_0001);
+        let negative_nan = f64::from_bits(0xfff8_0000_0000_0042);
+        let values = [
+            0.0,
+            -0.0,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            quiet_payload,
+            signalling,
+            negative_nan,
+            f64::MIN_POSITIVE / 3.0,
+            f64::MAX,
+            -0.0,
+            0.0,
+        ];
+        round_trip(&values, LOTUS_J2D1);
+        round_trip(&values, LOTUS_J3D1);
+    }
+
+    #[test]
+    fn gauge_series_compresses() {
+        let values: Vec<f64> = (0..1_000).map(|i| 20.0 + ((i / 50) as f64) * 0.5).collect();
+        let bytes = round_trip(&values, LOTUS_J2D1);
+        let bits_per_value = (bytes.len() * 8) as f64 / values.len() as f64;
+        assert!(bits_per_value < 8.0, "{bits_per_value} bits/value");
+    }
+
+    #[test]
+    fn empty_series() {
+        let bytes = round_trip(&[], LOTUS_J2D1);
+        assert_eq!(
+            F64SeriesDecoder::new(&bytes, 2, 1).next_value().unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn config_must_cover_window_fields() {
+        assert_eq!(
+            F64SeriesEncoder::new(1, 1).unwrap_err(),
+            LotusError::ValueTooLarge
+        );
+    }
+
+    #[test]
+    fn missing_end_marker_is_an_error() {
+        let mut encoder = F64SeriesEncoder::new(2, 1).unwrap();
+        encoder.push(1.5).unwrap();
+        let bytes = encoder.writer.clone().into_bytes();
+        let mut decoder = F64SeriesDecoder::new(&bytes, 2, 1);
+        assert_eq!(decoder.next_value().unwrap(), Some(1.5));
+        assert!(decoder.next_value().is_err());
+    }
+}
@@ -0,0 +1,194 @@
+//! Frame-of-reference blocks: one reference value per block, Lotus-coded residuals.
+
+use crate::{
+    BitReader, BitWriter, HEADER_CONFIG, LotusError, lotus_read_u64, lotus_write_u64,
+    zigzag_decode, zigzag_encode,
+};
+
+/// Reference value a frame-of-reference block subtracts from each value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ForReference {
+    /// Block minimum; residuals are plain non-negative offsets.
+    #[default]
+    Min,
+    /// Block median; residuals are signed offsets mapped through zigzag.
+    Median,
+}
+
+fn reference_value(values: &[u64], reference: ForReference) -> u64 {
+    match reference {
+        ForReference::Min => values.iter().copied().min().unwrap_or(0),
+        ForReference::Median => {
+            let mut sorted = values.to_vec();
+            sorted.sort_unstable();
+            sorted[sorted.len() / 2]
+        }
+    }
+}
+
+/// Append a frame-of-reference block to `writer`.
+///
+/// The value count and reference are Lotus-coded with [`LOTUS_J3D1`](crate::LOTUS_J3D1) so
+/// any `u64` fits; residuals use the caller's `(j_bits, tiers)`.
+pub fn for_write_block(
+    writer: &mut BitWriter,
+    values: &[u64],
+    reference: ForReference,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(), LotusError> {
+    let (hj, hd) = HEADER_CONFIG;
+    lotus_write_u64(writer, values.len() as u64, hj, hd)?;
+    if values.is_empty() {
+        return Ok(());
+    }
+    let base = reference_value(values, reference);
+    writer.write_bits((reference == ForReference::Median) as u64, 1)?;
+    lotus_write_u64(writer, base, hj, hd)?;
+    for &value in values {
+        let residual = match reference {
+            ForReference::Min => value - base,
+            ForReference::Median => zigzag_encode(value.wrapping_sub(base) as i64),
+        };
+        lotus_write_u64(writer, residual, j_bits, tiers)?;
+    }
+    Ok(())
+}
+
+/// Read a block written by [`for_write_block`].
+pub fn for_read_block(
+    reader: &mut BitReader<'_>,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u64>, LotusError> {
+    let mut values = Vec::new();
+    for_read_block_each(reader, j_bits, tiers, |value, _| values.push(value))?;
+    Ok(values)
+}
+
+/// Read a block, passing each value and the bit position just after its residual to `emit`.
+///
+/// Values already emitted when an error is returned decoded cleanly.
+pub(crate) fn for_read_block_each(
+    reader: &mut BitReader<'_>,
+    j_bits: usize,
+    tiers: usize,
+    mut emit: impl FnMut(u64, usize),
+) -> Result<usize, LotusError> {
+    let (hj, hd) = HEADER_CONFIG;
+    let count = lotus_read_u64(reader, hj, hd)?;
+    if count == 0 {
+        return Ok(0);
+    }
+    // Every residual needs at least one bit, so larger counts cannot be honest.
+    if count > reader.bits_remaining() as u64 {
+        return Err(LotusError::UnexpectedEof);
+    }
+    let median = reader.read_bits(1)? == 1;
+    let base = lotus_read_u64(reader, hj, hd)?;
+    for _ in 0..count {
+        let residual = lotus_read_u64(reader, j_bits, tiers)?;
+        let value = if median {
+            base.wrapping_add(zigzag_decode(residual) as u64)
+        } else {
+            base.checked_add(residual)
+                .ok_or(LotusError::ValueTooLarge)?
+        };
+        emit(value, reader.bits_consumed());
+    }
+    Ok(count as usize)
+}
+
+/// Encode `values` as a single byte-padded frame-of-reference block.
+pub fn for_encode_block(
+    values: &[u64],
+    reference: ForReference,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u8>, LotusError> {
+    let mut writer = BitWriter::new();
+    for_write_block(&mut writer, values, reference, j_bits, tiers)?;
+    Ok(writer.into_bytes())
+}
+
+/// Decode a block produced by [`for_encode_block`], returning the values and bits consumed.
+pub fn for_decode_block(
+    bytes: &[u8],
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(Vec<u64>, usize), LotusError> {
+    let mut reader = BitReader::new(bytes);
+    let values = for_read_block(&mut reader, j_bits, tiers)?;
+    Ok((values, reader.bits_consumed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, lotus_encoded_bits};
+
+    fn plain_bits(values: &[u64], cfg: (usize, usize)) -> usize {
+        values
+            .iter()
+            .map(|v| lotus_encoded_bits(*v, cfg.0, cfg.1).unwrap())
+            .sum()
+    }
+
+    #[test]
+    fn round_trip_both_references() {
+        let values = [
+            1_700_000_123u64,
+            1_700_000_000,
+            1_700_000_999,
+            1_700_000_500,
+        ];
+        for reference in [ForReference::Min, ForReference::Median] {
+            let encoded = for_encode_block(&values, reference, LOTUS_J2D1.0, LOTUS_J2D1.1).unwrap();
+            let (decoded, _) = for_decode_block(&encoded, LOTUS_J2D1.0, LOTUS_J2D1.1).unwrap();
+            assert_eq!(decoded, values);
+        }
+    }
+
+    #[test]
+    fn empty_and_extreme_blocks() {
+        let (j_bits, tiers) = (3, 2);
+        for values in [vec![], vec![0, u64::MAX], vec![u64::MAX; 3]] {
+            for reference in [ForReference::Min, ForReference::Median] {
+                let encoded = for_encode_block(&values, reference, j_bits, tiers).unwrap();
+                let (decoded, _) = for_decode_block(&encoded, j_bits, tiers).unwrap();
+                assert_eq!(decoded, values);
+            }
+        }
+    }
+
+    #[test]
+    fn smaller_than_plain_lotus_for_clustered_epochs() {
+        let (j_bits, tiers) = (3, 1);
+        let values: Vec<u64> = (0..256).map(|i| 1_700_000_000 + (i * 37) % 1_000).collect();
+        let encoded = for_encode_block(&values, ForReference::Min, j_bits, tiers).unwrap();
+        let (_, for_bits) = for_decode_block(&encoded, j_bits, tiers).unwrap();
+        let plain = plain_bits(&values, (j_bits, tiers));
+        assert!(
+            for_bits * 2 < plain,
+            "FOR {for_bits} bits vs plain {plain} bits"
+        );
+    }
+
+    #[test]
+    fn median_reference_helps_with_outliers_below() {
+        let (j_bits, tiers) = (3, 1);
+        let mut values: Vec<u64> = (0..128).map(|i| 5_000_000 + i).collect();
+        values.push(0);
+        let min_block = for_encode_block(&values, ForReference::Min, j_bits, tiers).unwrap();
+        let median_block = for_encode_block(&values, ForReference::Median, j_bits, tiers).unwrap();
+        assert!(median_block.len() < min_block.len());
+        assert!(median_block.len() * 8 < plain_bits(&values, (j_bits, tiers)));
+    }
+
+    #[test]
+    fn truncated_block_is_eof() {
+        let encoded = for_encode_block(&[10, 20, 30], ForReference::Min, 2, 1).unwrap();
+        let err = for_decode_block(&encoded[..1], 2, 1).unwrap_err();
+        assert_eq!(err, LotusError::UnexpectedEof);
+    }
+}
@@ -0,0 +1,338 @@
+//! Lane-interleaved layout: values are dealt round-robin into independent Lotus streams.
+//!
+//! A sequential Lotus stream cannot locate value `i + 1` before value `i` is fully decoded.
+//! Splitting the values across `K` lanes, each with its own byte-aligned bitstream, gives the
+//! decoder `K` independent cursors it can advance in lockstep. Groups of four lanes share one
+//! bit-extraction kernel call, which uses SSE2/AVX2 when the `simd` feature is enabled.
+
+use crate::{
+    BitReader, BitWriter, HEADER_CONFIG, LotusError, lotus_decode_value, lotus_read_u64,
+    lotus_write_u64, max_width_for_config,
+};
+
+/// Number of lanes decoded together by one kernel call.
+const GROUP: usize = 4;
+
+/// Extract `width[i]` bits starting at bit `pos[i]` of `data` for each of four lanes.
+///
+/// Callers guarantee `width[i] <= 56` and `pos[i] / 8 + 8 <= data.len()`, so every field sits
+/// inside one unaligned big-endian 64-bit window.
+pub(crate) type ExtractKernel = fn(&[u8], &[usize; GROUP], &[usize; GROUP]) -> [u64; GROUP];
+
+/// Largest field the kernels handle: a 64-bit window minus up to 7 bits of misalignment.
+pub(crate) const KERNEL_MAX_WIDTH: usize = 56;
+
+/// Portable kernel; with `simd` on x86_64 it remains the reference the SIMD kernels test against.
+#[cfg_attr(all(feature = "simd", target_arch = "x86_64"), allow(dead_code))]
+pub(crate) fn extract_scalar(
+    data: &[u8],
+    pos: &[usize; GROUP],
+    width: &[usize; GROUP],
+) -> [u64; GROUP] {
+    let mut out = [0u64; GROUP];
+    for lane in 0..GROUP {
+        let offset = pos[lane] / 8;
+        let mut window = [0u8; 8];
+        window.copy_from_slice(&data[offset..offset + 8]);
+        let word = u64::from_be_bytes(window) << (pos[lane] % 8);
+        out[lane] = word >> (64 - width[lane]);
+    }
+    out
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+fn select_kernel() -> ExtractKernel {
+    crate::simd::select_kernel()
+}
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+fn select_kernel() -> ExtractKernel {
+    extract_scalar
+}
+
+/// Read a field of up to 64 bits at an arbitrary bit offset.
+fn peek_bits(data: &[u8], pos: usize, width: usize) -> u64 {
+    let first = pos / 8;
+    let last = (pos + width).div_ceil(8);
+    let mut window = 0u128;
+    for &byte in &data[first..last] {
+        window = (window << 8) | byte as u128;
+    }
+    let trailing = last * 8 - (pos + width);
+    ((window >> trailing) & ((1u128 << width) - 1)) as u64
+}
+
+/// Encode `values` into `lanes` interleaved Lotus streams; value `i` goes to lane `i % lanes`.
+///
+/// The header records the lane count, value count and each lane's bit length with
+/// [`LOTUS_J3D1`](crate::LOTUS_J3D1); lane streams follow, each starting on a byte boundary.
+/// Multiples of four lanes let the decoder keep every kernel call full.
+pub fn interleave_encode(
+    values: &[u64],
+    lanes: usize,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u8>, LotusError> {
+    if lanes == 0 {
+        return Err(LotusError::InvalidEncoding);
+    }
+    let mut writers = vec![BitWriter::new(); lanes];
+    for (index, &value) in values.iter().enumerate() {
+        lotus_write_u64(&mut writers[index % lanes], value, j_bits, tiers)?;
+    }
+
+    let (hj, hd) = HEADER_CONFIG;
+    let mut header = BitWriter::new();
+    lotus_write_u64(&mut header, lanes as u64, hj, hd)?;
+    lotus_write_u64(&mut header, values.len() as u64, hj, hd)?;
+    for writer in &writers {
+        lotus_write_u64(&mut header, writer.bits_written() as u64, hj, hd)?;
+    }
+    let mut out = header.into_bytes();
+    for writer in writers {
+        out.extend_from_slice(&writer.into_bytes());
+    }
+    Ok(out)
+}
+
+/// Lane streams located inside an interleaved buffer.
+struct Lanes<'a> {
+    data: &'a [u8],
+    /// Absolute bit position where each lane's stream starts within `data`.
+    start: Vec<usize>,
+    /// Absolute bit position one past each lane's last valid bit.
+    end: Vec<usize>,
+    j_bits: usize,
+    tiers: usize,
+    max_width: u128,
+    kernel: ExtractKernel,
+}
+
+impl Lanes<'_> {
+    /// Extract the next field from each lane in `lanes`, advancing `pos`.
+    fn extract(
+        &self,
+        lanes: &[usize; GROUP],
+        pos: &mut [usize; GROUP],
+        width: &[usize; GROUP],
+    ) -> Result<[u64; GROUP], LotusError> {
+        for i in 0..GROUP {
+            if width[i] > self.end[lanes[i]] - pos[i] {
+                return Err(LotusError::UnexpectedEof);
+            }
+            if width[i] > 64 {
+                return Err(LotusError::ValueTooLarge);
+            }
+        }
+        let fast =
+            (0..GROUP).all(|i| width[i] <= KERNEL_MAX_WIDTH && pos[i] / 8 + 8 <= self.data.len());
+        let raw = if fast {
+            (self.kernel)(self.data, pos, width)
+        } else {
+            std::array::from_fn(|i| peek_bits(self.data, pos[i], width[i]))
+        };
+        for i in 0..GROUP {
+            pos[i] += width[i];
+        }
+        Ok(raw)
+    }
+
+    /// Decode one codeword from each of four lanes at once.
+    fn decode_group(
+        &self,
+        lanes: &[usize; GROUP],
+        pos: &mut [usize; GROUP],
+    ) -> Result<[u64; GROUP], LotusError> {
+        let jump = self.extract(lanes, pos, &[self.j_bits; GROUP])?;
+        let mut width = [0usize; GROUP];
+        for i in 0..GROUP {
+            width[i] = jump[i] as usize + 1;
+            if width[i] as u128 > self.max_width {
+                return Err(LotusError::ValueTooLarge);
+            }
+        }
+        for _ in 0..self.tiers {
+            let raw = self.extract(lanes, pos, &width)?;
+            for i in 0..GROUP {
+                let width_value = lotus_decode_value(raw[i], width[i])? as usize;
+                if width_value == 0 || width_value as u128 > self.max_width {
+                    return Err(LotusError::ValueTooLarge);
+                }
+                width[i] = width_value;
+            }
+        }
+        let raw = self.extract(lanes, pos, &width)?;
+        let mut values = [0u64; GROUP];
+        for i in 0..GROUP {
+            values[i] = lotus_decode_value(raw[i], width[i])?;
+        }
+        Ok(values)
+    }
+
+    /// Decode `count` codewords from a single lane starting at bit `pos`.
+    fn decode_lane(
+        &self,
+        lane: usize,
+        pos: usize,
+        count: usize,
+        mut emit: impl FnMut(usize, u64),
+    ) -> Result<(), LotusError> {
+        let first = self.start[lane] / 8;
+        let last = self.end[lane].div_ceil(8);
+        let mut reader = BitReader::new(&self.data[first..last]);
+        let mut skip = pos - self.start[lane];
+        while skip > 0 {
+            let take = skip.min(64);
+            reader.read_bits(take)?;
+            skip -= take;
+        }
+        for round in 0..count {
+            let value = lotus_read_u64(&mut reader, self.j_bits, self.tiers)?;
+            if self.start[lane] + reader.bits_consumed() > self.end[lane] {
+                return Err(LotusError::UnexpectedEof);
+            }
+            emit(round, value);
+        }
+        Ok(())
+    }
+}
+
+/// Decode a buffer produced by [`interleave_encode`].
+pub fn interleave_decode(
+    bytes: &[u8],
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u64>, LotusError> {
+    if !(1..=8).contains(&j_bits) || tiers == 0 {
+        return Err(LotusError::InvalidEncoding);
+    }
+    let (hj, hd) = HEADER_CONFIG;
+    let mut reader = BitReader::new(bytes);
+    let lane_count = lotus_read_u64(&mut reader, hj, hd)?;
+    let count = lotus_read_u64(&mut reader, hj, hd)?;
+    if lane_count == 0 {
+        return Err(LotusError::InvalidEncoding);
+    }
+    // Each lane length and each value takes at least one bit.
+    if lane_count.saturating_add(count) > reader.bits_remaining() as u64 {
+        return Err(LotusError::UnexpectedEof);
+    }
+    let lane_count = lane_count as usize;
+    let count = count as usize;
+
+    let mut start = Vec::with_capacity(lane_count);
+    let mut end = Vec::with_capacity(lane_count);
+    let mut cursor = 0usize;
+    for _ in 0..lane_count {
+        let bits = usize::try_from(lotus_read_u64(&mut reader, hj, hd)?)
+            .map_err(|_| LotusError::UnexpectedEof)?;
+        start.push(cursor);
+        end.push(cursor.checked_add(bits).ok_or(LotusError::UnexpectedEof)?);
+        cursor = cursor
+            .checked_add(bits.div_ceil(8) * 8)
+            .ok_or(LotusError::UnexpectedEof)?;
+    }
+    let data = &bytes[reader.bits_consumed().div_ceil(8)..];
+    if cursor > data.len() * 8 {
+        return Err(LotusError::UnexpectedEof);
+    }
+
+    let lanes = Lanes {
+        data,
+        start,
+        end,
+        j_bits,
+        tiers,
+        max_width: max_width_for_config(j_bits, tiers),
+        kernel: select_kernel(),
+    };
+    let per_lane = |lane: usize| count / lane_count + usize::from(lane < count % lane_count);
+
+    let mut out = vec![0u64; count];
+    let mut group_start = 0;
+    while group_start < lane_count {
+        let group_end = (group_start + GROUP).min(lane_count);
+        let mut pos: Vec<usize> = lanes.start[group_start..group_end].to_vec();
+        let mut decoded = 0;
+        if group_end - group_start == GROUP {
+            let ids: [usize; GROUP] = std::array::from_fn(|i| group_start + i);
+            let mut group_pos: [usize; GROUP] = std::array::from_fn(|i| pos[i]);
+            // The last lane of a group never holds more values than the others.
+            decoded = per_lane(group_end - 1);
+            for round in 0..decoded {
+                let values = lanes.decode_group(&ids, &mut group_pos)?;
+                for (i, value) in values.into_iter().enumerate() {
+                    out[round * lane_count + ids[i]] = value;
+                }
+            }
+            pos = group_pos.to_vec();
+        }
+        for (i, lane) in (group_start..group_end).enumerate() {
+            lanes.decode_lane(lane, pos[i], per_lane(lane) - decoded, |round, value| {
+                out[(decoded + round) * lane_count + lane] = value;
+            })?;
+        }
+        group_start = group_end;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1};
+
+    fn sample(count: u64) -> Vec<u64> {
+        (0..count)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (i % 64))
+            .collect()
+    }
+
+    #[test]
+    fn round_trip_lane_counts() {
+        let values = sample(1_003);
+        for lanes in [1, 2, 3, 4, 5, 8, 16] {
+            let encoded = interleave_encode(&values, lanes, LOTUS_J3D1.0, LOTUS_J3D1.1).unwrap();
+            let decoded = interleave_decode(&encoded, LOTUS_J3D1.0, LOTUS_J3D1.1).unwrap();
+            assert_eq!(decoded, values, "lanes = {lanes}");
+        }
+    }
+
+    #[test]
+    fn short_inputs_and_extremes() {
+        for values in [vec![], vec![7], vec![u64::MAX; 9], vec![0, 1, 2]] {
+            let encoded = interleave_encode(&values, 4, 3, 2).unwrap();
+            assert_eq!(interleave_decode(&encoded, 3, 2).unwrap(), values);
+        }
+    }
+
+    #[test]
+    fn matches_sequential_size() {
+        let values: Vec<u64> = (0..512).collect();
+        let (j_bits, tiers) = LOTUS_J2D1;
+        let encoded = interleave_encode(&values, 8, j_bits, tiers).unwrap();
+        let mut writer = BitWriter::new();
+        for &value in &values {
+            lotus_write_u64(&mut writer, value, j_bits, tiers).unwrap();
+        }
+        // Lane padding and the header cost a handful of bytes at most.
+        assert!(encoded.len() <= writer.into_bytes().len() + 24);
+    }
+
+    #[test]
+    fn truncated_lanes_are_eof() {
+        let values = sample(64);
+        let encoded = interleave_encode(&values, 4, LOTUS_J3D1.0, LOTUS_J3D1.1).unwrap();
+        let err = interleave_decode(&encoded[..encoded.len() - 3], LOTUS_J3D1.0, LOTUS_J3D1.1)
+            .unwrap_err();
+        assert_eq!(err, LotusError::UnexpectedEof);
+    }
+
+    #[test]
+    fn zero_lanes_rejected() {
+        assert_eq!(
+            interleave_encode(&[1], 0, 2, 1).unwrap_err(),
+            LotusError::InvalidEncoding
+        );
+    }
+}
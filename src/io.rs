@@ -0,0 +1,170 @@
+//! `std::io` extension traits for byte-aligned codewords, in the style of `byteorder`.
+
+use std::io::{self, Read, Write};
+
+use crate::{
+    BitReader, LotusError, lotus_decode_u64, lotus_decode_value, lotus_encode_u64,
+    max_width_for_config, zigzag_decode, zigzag_encode,
+};
+
+fn invalid_data(err: LotusError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Length in bits of the codeword starting `bytes`, as far as the fields it holds tell: the end of
+/// the first field it does not hold yet, or the whole codeword once the payload is in.
+///
+/// Widths are checked as [`lotus_read_u64`](crate::lotus_read_u64) checks them.
+fn known_len(bytes: &[u8], j_bits: usize, tiers: usize) -> Result<usize, LotusError> {
+    let max_width = max_width_for_config(j_bits, tiers);
+    let mut reader = BitReader::new(bytes);
+    let mut width = j_bits;
+    for field in 0..=tiers + 1 {
+        let start = reader.bits_consumed();
+        let Ok(bits) = reader.read_bits(width) else {
+            return Ok(start + width);
+        };
+        width = match field {
+            0 => bits as usize + 1,
+            _ if field <= tiers => lotus_decode_value(bits, width)? as usize,
+            _ => break,
+        };
+        if width == 0 || width > 64 || width as u128 > max_width {
+            return Err(LotusError::ValueTooLarge);
+        }
+    }
+    Ok(reader.bits_consumed())
+}
+
+/// Codeword readers for any [`Read`].
+pub trait ReadLotusExt: Read {
+    /// Read one byte-aligned codeword, consuming exactly its bytes.
+    ///
+    /// The jumpstarter and each tier field give the width of the next field, so every read asks
+    /// for just the bytes up to the end of the next field and nothing past the codeword is read.
+    /// A stream ending mid-codeword yields [`io::ErrorKind::UnexpectedEof`]; a malformed codeword
+    /// yields [`io::ErrorKind::InvalidData`].
+    fn read_lotus_u64(&mut self, j_bits: usize, tiers: usize) -> io::Result<u64> {
+        if !(1..=8).contains(&j_bits) || tiers == 0 {
+            return Err(invalid_data(LotusError::InvalidEncoding));
+        }
+        let mut bytes = Vec::with_capacity(16);
+        loop {
+            let len = known_len(&bytes, j_bits, tiers)
+                .map_err(invalid_data)?
+                .div_ceil(8);
+            if len <= bytes.len() {
+                break;
+            }
+            let start = bytes.len();
+            bytes.resize(len, 0);
+            self.read_exact(&mut bytes[start..])?;
+        }
+        lotus_decode_u64(&bytes, j_bits, tiers)
+            .map(|(value, _)| value)
+            .map_err(invalid_data)
+    }
+
+    /// Read a zigzag-mapped signed value written by [`WriteLotusExt::write_lotus_i64`].
+    fn read_lotus_i64(&mut self, j_bits: usize, tiers: usize) -> io::Result<i64> {
+        self.read_lotus_u64(j_bits, tiers).map(zigzag_decode)
+    }
+}
+
+impl<R: Read + ?Sized> ReadLotusExt for R {}
+
+/// Codeword writers for any [`Write`].
+pub trait WriteLotusExt: Write {
+    /// Write one codeword, zero-padded to a whole byte. Nothing is written if encoding fails.
+    fn write_lotus_u64(&mut self, value: u64, j_bits: usize, tiers: usize) -> io::Result<()> {
+        let bytes = lotus_encode_u64(value, j_bits, tiers)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        self.write_all(&bytes)
+    }
+
+    /// Write a signed value as the codeword of its zigzag mapping.
+    fn write_lotus_i64(&mut self, value: i64, j_bits: usize, tiers: usize) -> io::Result<()> {
+        self.write_lotus_u64(zigzag_encode(value), j_bits, tiers)
+    }
+}
+
+impl<W: Write + ?Sized> WriteLotusExt for W {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1};
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_exactly_each_codeword() {
+        let (j_bits, tiers) = LOTUS_J3D1;
+        let values = [0u64, 1, 300, 1 << 33, u64::MAX];
+        let mut out = Vec::new();
+        for value in values {
+            out.write_lotus_u64(value, j_bits, tiers).unwrap();
+        }
+        out.write_lotus_i64(-42, j_bits, tiers).unwrap();
+        out.extend_from_slice(b"tail");
+
+        let mut cursor = Cursor::new(out);
+        for value in values {
+            let start = cursor.position() as usize;
+            assert_eq!(cursor.read_lotus_u64(j_bits, tiers).unwrap(), value);
+            let expected = lotus_encode_u64(value, j_bits, tiers).unwrap().len();
+            assert_eq!(cursor.position() as usize - start, expected);
+        }
+        assert_eq!(cursor.read_lotus_i64(j_bits, tiers).unwrap(), -42);
+        let mut tail = Vec::new();
+        cursor.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, b"tail");
+    }
+
+    #[test]
+    fn matches_the_slice_decoder() {
+        // 3-byte prefixes across the whole range, padded so that no codeword runs out of input.
+        for (j_bits, tiers) in [(1, 1), (2, 1), (1, 2), (3, 1), (8, 1)] {
+            for prefix in (0..1u32 << 24).step_by(97) {
+                let mut bytes = prefix.to_be_bytes()[1..].to_vec();
+                bytes.extend_from_slice(&[0xa5; 40]);
+                let mut cursor = Cursor::new(&bytes);
+                match lotus_decode_u64(&bytes, j_bits, tiers) {
+                    Ok((value, bits)) => {
+                        assert_eq!(cursor.read_lotus_u64(j_bits, tiers).unwrap(), value);
+                        assert_eq!(cursor.position() as usize, bits.div_ceil(8));
+                    }
+                    Err(err) => {
+                        let read = cursor.read_lotus_u64(j_bits, tiers).unwrap_err();
+                        assert_eq!(LotusError::from(read), err, "{prefix:06x}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn error_kinds() {
+        let (j_bits, tiers) = LOTUS_J2D1;
+        let bytes = lotus_encode_u64(1 << 20, j_bits, tiers).unwrap();
+        let mut truncated = &bytes[..bytes.len() - 1];
+        assert_eq!(
+            truncated.read_lotus_u64(j_bits, tiers).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+
+        let mut invalid: &[u8] = &[0x00];
+        assert_eq!(
+            invalid.read_lotus_u64(j_bits, tiers).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        let mut sink = Vec::new();
+        assert_eq!(
+            sink.write_lotus_u64(u64::MAX, j_bits, tiers)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert!(sink.is_empty());
+    }
+}
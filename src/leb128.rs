@@ -0,0 +1,271 @@
+//! LEB128 varints, as used by protobuf, DWARF and WebAssembly, and transcoding to and from packed
+//! Lotus streams.
+//!
+//! Readers are strict: a varint must end within ten bytes, fit in 64 bits and use as few bytes as
+//! possible. Overlong forms such as `0x80 0x00` for zero are [`LotusError::InvalidEncoding`], so a
+//! stream has exactly one encoding and transcoding round-trips byte for byte.
+
+use std::convert::Infallible;
+
+use crate::{
+    BitReader, BitWriter, LotusError, lotus_read_u64, lotus_write_u64, zigzag_decode, zigzag_encode,
+};
+
+/// How the integers in a LEB128 stream are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Leb128Kind {
+    /// Unsigned LEB128, protobuf's `uint64` varint.
+    #[default]
+    Unsigned,
+    /// Signed LEB128 (SLEB128); zigzagged on the Lotus side.
+    Signed,
+}
+
+/// Append the unsigned LEB128 encoding of `value` to `out`.
+pub fn leb128_write(out: &mut Vec<u8>, value: u64) {
+    let Ok(()) = leb128_encode_with(value, |byte| {
+        out.push(byte);
+        Ok::<_, Infallible>(())
+    });
+}
+
+/// Pass the bytes of the unsigned LEB128 encoding of `value` to `emit`, low group first.
+pub(crate) fn leb128_encode_with<E>(
+    mut value: u64,
+    mut emit: impl FnMut(u8) -> Result<(), E>,
+) -> Result<(), E> {
+    while value >= 0x80 {
+        emit(value as u8 | 0x80)?;
+        value >>= 7;
+    }
+    emit(value as u8)
+}
+
+/// Unsigned LEB128 encoding of `value` as a new buffer.
+pub fn leb128_encode(value: u64) -> Vec<u8> {
+    let mut out = Vec::with_capacity(leb128_len(value));
+    leb128_write(&mut out, value);
+    out
+}
+
+/// Encoded length of `value` in bytes.
+pub fn leb128_len(value: u64) -> usize {
+    (64 - value.leading_zeros() as usize).max(1).div_ceil(7)
+}
+
+/// Decode one unsigned varint from the bytes `next_byte` yields, asking for none past its end.
+pub(crate) fn leb128_decode_with(
+    mut next_byte: impl FnMut() -> Result<u8, LotusError>,
+) -> Result<u64, LotusError> {
+    let mut value = 0u64;
+    for (index, shift) in (0..64).step_by(7).enumerate() {
+        let byte = next_byte()?;
+        let bits = u64::from(byte & 0x7f);
+        // The tenth byte only has room for bit 63.
+        if shift == 63 && bits > 1 {
+            return Err(LotusError::InvalidEncoding);
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && index > 0 {
+                return Err(LotusError::InvalidEncoding);
+            }
+            return Ok(value);
+        }
+    }
+    Err(LotusError::InvalidEncoding)
+}
+
+/// Read one unsigned varint at `*pos`, advancing `pos` past it only on success.
+pub fn leb128_read(bytes: &[u8], pos: &mut usize) -> Result<u64, LotusError> {
+    let mut len = 0;
+    let value = leb128_decode_with(|| {
+        let byte = bytes.get(*pos + len).ok_or(LotusError::UnexpectedEof)?;
+        len += 1;
+        Ok(*byte)
+    })?;
+    *pos += len;
+    Ok(value)
+}
+
+/// Append the signed LEB128 encoding of `value` to `out`.
+pub fn sleb128_write(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let sign = byte & 0x40 != 0;
+        if (value == 0 && !sign) || (value == -1 && sign) {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Encoded length of `value` in bytes.
+pub fn sleb128_len(value: i64) -> usize {
+    // Magnitude bits plus a sign bit.
+    (65 - (value ^ (value >> 63)).leading_zeros() as usize).div_ceil(7)
+}
+
+/// Read one signed varint at `*pos`, advancing `pos` past it only on success.
+pub fn sleb128_read(bytes: &[u8], pos: &mut usize) -> Result<i64, LotusError> {
+    let mut value = 0i64;
+    for (index, shift) in (0..64).step_by(7).enumerate() {
+        let byte = *bytes.get(*pos + index).ok_or(LotusError::UnexpectedEof)?;
+        value |= i64::from(byte & 0x7f) << shift;
+        if byte & 0x80 != 0 {
+            continue;
+        }
+        let sign = byte & 0x40 != 0;
+        if shift == 63 {
+            // The tenth byte holds bit 63 and must otherwise be pure sign extension.
+            if byte != 0x00 && byte != 0x7f {
+                return Err(LotusError::InvalidEncoding);
+            }
+        } else if sign {
+            value |= -1 << (shift + 7);
+        }
+        // A final byte that only repeats the previous byte's sign is overlong.
+        if index > 0 {
+            let previous_sign = bytes[*pos + index - 1] & 0x40 != 0;
+            if (byte == 0x00 && !previous_sign) || (byte == 0x7f && previous_sign) {
+                return Err(LotusError::InvalidEncoding);
+            }
+        }
+        *pos += index + 1;
+        return Ok(value);
+    }
+    Err(LotusError::InvalidEncoding)
+}
+
+/// Transcode a whole LEB128 stream into a packed Lotus stream, returning it with the value count.
+///
+/// Signed varints are zigzagged, as [`WriteLotusExt`](crate::WriteLotusExt) does for `i64`.
+pub fn leb128_to_lotus(
+    varints: &[u8],
+    kind: Leb128Kind,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(Vec<u8>, usize), LotusError> {
+    let mut writer = BitWriter::new();
+    let (mut pos, mut count) = (0, 0);
+    while pos < varints.len() {
+        let value = match kind {
+            Leb128Kind::Unsigned => leb128_read(varints, &mut pos)?,
+            Leb128Kind::Signed => zigzag_encode(sleb128_read(varints, &mut pos)?),
+        };
+        lotus_write_u64(&mut writer, value, j_bits, tiers)?;
+        count += 1;
+    }
+    Ok((writer.into_bytes(), count))
+}
+
+/// Transcode `count` values of a packed Lotus stream back into LEB128.
+///
+/// The stream must end within the byte holding its last codeword, as written by
+/// [`leb128_to_lotus`].
+pub fn lotus_to_leb128(
+    packed: &[u8],
+    count: usize,
+    kind: Leb128Kind,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u8>, LotusError> {
+    let mut reader = BitReader::new(packed);
+    let mut out = Vec::with_capacity(packed.len() * 2);
+    for _ in 0..count {
+        let value = lotus_read_u64(&mut reader, j_bits, tiers)?;
+        match kind {
+            Leb128Kind::Unsigned => leb128_write(&mut out, value),
+            Leb128Kind::Signed => sleb128_write(&mut out, zigzag_decode(value)),
+        }
+    }
+    if reader.bits_consumed().div_ceil(8) != packed.len() {
+        return Err(LotusError::InvalidEncoding);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LOTUS_J3D1;
+
+    #[test]
+    fn known_encodings_and_lengths() {
+        assert_eq!(leb128_encode(0), [0x00]);
+        assert_eq!(leb128_encode(127), [0x7f]);
+        assert_eq!(leb128_encode(300), [0xac, 0x02]);
+        assert_eq!(leb128_encode(u64::MAX).len(), 10);
+        let mut out = Vec::new();
+        for value in [0i64, -1, 63, -64, 64, -65, i64::MIN, i64::MAX] {
+            out.clear();
+            sleb128_write(&mut out, value);
+            assert_eq!(out.len(), sleb128_len(value), "{value}");
+            let mut pos = 0;
+            assert_eq!(sleb128_read(&out, &mut pos), Ok(value));
+            assert_eq!(pos, out.len());
+        }
+        sleb128_write(&mut out, -123_456);
+        assert_eq!(out[10..], [0xc0, 0xbb, 0x78]);
+        for value in [0, 1, 127, 128, 1 << 35, u64::MAX] {
+            let bytes = leb128_encode(value);
+            assert_eq!(bytes.len(), leb128_len(value));
+            assert_eq!(leb128_read(&bytes, &mut 0), Ok(value));
+        }
+    }
+
+    #[test]
+    fn rejects_overlong_overflowing_and_truncated() {
+        let mut pos = 0;
+        for bad in [&[0x80, 0x00][..], &[0xff, 0x00], &[0xff; 10], &[0x80; 9]] {
+            assert!(leb128_read(bad, &mut pos).is_err(), "{bad:02x?}");
+        }
+        let mut overflow = vec![0xff; 9];
+        overflow.push(0x02);
+        assert_eq!(
+            leb128_read(&overflow, &mut pos),
+            Err(LotusError::InvalidEncoding)
+        );
+        assert_eq!(
+            leb128_read(&[0x80], &mut pos),
+            Err(LotusError::UnexpectedEof)
+        );
+        for bad in [&[0x80, 0x00][..], &[0xff, 0x7f], &[0xc0]] {
+            assert!(sleb128_read(bad, &mut pos).is_err(), "{bad:02x?}");
+        }
+        let mut wide = vec![0x80; 9];
+        wide.push(0x01);
+        assert_eq!(
+            sleb128_read(&wide, &mut pos),
+            Err(LotusError::InvalidEncoding)
+        );
+        assert_eq!(pos, 0);
+        // -64 fits in one byte, but 64 needs a second one to carry its sign.
+        assert_eq!(sleb128_read(&[0xc0, 0x00], &mut pos), Ok(64));
+    }
+
+    #[test]
+    fn transcodes_both_ways() {
+        let (j_bits, tiers) = LOTUS_J3D1;
+        let values = [0u64, 1, 300, 1 << 40, u64::MAX, 7];
+        let mut varints = Vec::new();
+        values.iter().for_each(|&v| leb128_write(&mut varints, v));
+        let (packed, count) =
+            leb128_to_lotus(&varints, Leb128Kind::Unsigned, j_bits, tiers).unwrap();
+        assert_eq!(count, values.len());
+        let back = lotus_to_leb128(&packed, count, Leb128Kind::Unsigned, j_bits, tiers).unwrap();
+        assert_eq!(back, varints);
+
+        let signed = [0i64, -1, 1, -300, i64::MIN, i64::MAX];
+        let mut varints = Vec::new();
+        signed.iter().for_each(|&v| sleb128_write(&mut varints, v));
+        let (packed, count) = leb128_to_lotus(&varints, Leb128Kind::Signed, 2, 2).unwrap();
+        let back = lotus_to_leb128(&packed, count, Leb128Kind::Signed, 2, 2).unwrap();
+        assert_eq!(back, varints);
+
+        assert!(leb128_to_lotus(&[0x80, 0x00], Leb128Kind::Unsigned, j_bits, tiers).is_err());
+        assert!(lotus_to_leb128(&packed, count - 1, Leb128Kind::Signed, 2, 2).is_err());
+    }
+}
@@ -1,4 +1,5 @@
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "simd"), forbid(unsafe_code))]
+#![cfg_attr(feature = "simd", deny(unsafe_code))]
 
 use thiserror::Error;
 #[cfg(feature = "bigint")]
@@ -6,8 +7,73 @@ use num_bigint::BigUint;
 #[cfg(feature = "bigint")]
 use num_traits::One;
 
+mod analysis;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "bytes")]
+mod buf;
+mod chunked;
+mod container;
+mod envelope;
+mod explain;
+#[cfg(feature = "tokio")]
+mod codec;
+mod float;
+mod frame_of_reference;
+mod interleave;
+mod io;
+mod leb128;
+mod selfcheck;
+mod sequence;
+#[cfg(feature = "serde")]
+mod serde_format;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[allow(unsafe_code)]
+mod simd;
+mod traits;
+mod universal;
+
+pub use analysis::{ANALYSIS_MAX_J_BITS, ANALYSIS_MAX_TIERS, Analysis, Analyzer, LotusCost};
+#[cfg(feature = "arrow")]
+pub use arrow::{decode_array, encode_array};
+#[cfg(feature = "bytes")]
+pub use buf::{BitCarry, LotusBufExt, LotusBufMutExt};
+pub use chunked::{chunked_decode, chunked_encode};
+#[cfg(feature = "rayon")]
+pub use chunked::{par_chunked_decode, par_chunked_encode};
+pub use container::{
+    ContainerReader, ContainerWriter, Corruption, DEFAULT_BLOCK_LEN, MAX_BLOCK_LEN, Verification,
+};
+#[cfg(feature = "tokio")]
+pub use codec::LotusLengthCodec;
+pub use envelope::{EnvelopeRow, lotus_envelope, lotus_max_value};
+pub use explain::{Codeword, CodewordField};
+pub use float::{F64SeriesDecoder, F64SeriesEncoder};
+pub use frame_of_reference::{
+    ForReference, for_decode_block, for_encode_block, for_read_block, for_write_block,
+};
+pub use interleave::{interleave_decode, interleave_encode};
+pub use io::{ReadLotusExt, WriteLotusExt};
+pub use leb128::{
+    Leb128Kind, leb128_encode, leb128_len, leb128_read, leb128_to_lotus, leb128_write,
+    lotus_to_leb128, sleb128_len, sleb128_read, sleb128_write,
+};
+#[cfg(feature = "derive")]
+pub use lotus_derive::{LotusDecode, LotusEncode};
+pub use selfcheck::{ConfigCheck, MAX_CHECK_BITS, verify_config};
+pub use sequence::{SequenceCodec, sequence_read, sequence_write};
+#[cfg(feature = "serde")]
+pub use serde_format::{
+    Deserializer, Serializer, from_slice, from_slice_with_config, to_vec, to_vec_with_config,
+};
+pub use traits::{LotusDecode, LotusEncode};
+pub use universal::{
+    EliasDelta, EliasGamma, EliasOmega, ExpGolomb, Fibonacci, GOLOMB_RICE_MAX_QUOTIENT,
+    GolombRice, IntegerCodec, Leb128, LotusCodec,
+};
+
 /// Errors emitted by Lotus codecs.
-#[derive(Debug, Error, PartialEq, Eq)]
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
 pub enum LotusError {
     #[error("payload length exceeds jumpstarter capacity")]
     JumpstarterOverflow,
@@ -17,6 +83,33 @@ pub enum LotusError {
     InvalidEncoding,
     #[error("value exceeds algorithmic range for this (J,d) configuration")]
     ValueTooLarge,
+    #[error("{0}")]
+    Message(String),
+    #[error("I/O error: {message}")]
+    Io {
+        kind: std::io::ErrorKind,
+        /// The operating system's description, which may name the file involved.
+        message: String,
+    },
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+}
+
+/// Errors raised by [`ReadLotusExt`] and [`WriteLotusExt`] keep their original `LotusError`.
+impl From<std::io::Error> for LotusError {
+    fn from(err: std::io::Error) -> Self {
+        let inner = err.get_ref().and_then(|inner| inner.downcast_ref::<LotusError>());
+        if let Some(inner) = inner {
+            return inner.clone();
+        }
+        match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => Self::UnexpectedEof,
+            kind => Self::Io {
+                kind,
+                message: err.to_string(),
+            },
+        }
+    }
 }
 
 /// Streaming bit writer that appends to an owned buffer.
@@ -32,6 +125,18 @@ impl BitWriter {
         Self::default()
     }
 
+    /// Number of bits written so far, including bits still pending in the final byte.
+    pub fn bits_written(&self) -> usize {
+        self.buffer.len() * 8 + self.pending_bits as usize
+    }
+
+    /// Discard everything written, keeping the buffer's allocation for reuse.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.pending = 0;
+        self.pending_bits = 0;
+    }
+
     pub fn into_bytes(mut self) -> Vec<u8> {
         if self.pending_bits > 0 {
             self.buffer.push(self.pending << (8 - self.pending_bits));
@@ -67,6 +172,18 @@ impl BitWriter {
         }
         Ok(())
     }
+
+    /// Append every bit written to `other`, continuing at this writer's bit position.
+    pub fn append(&mut self, other: &BitWriter) -> Result<(), LotusError> {
+        if self.pending_bits == 0 {
+            self.buffer.extend_from_slice(&other.buffer);
+        } else {
+            for &byte in &other.buffer {
+                self.write_bits(byte as u64, 8)?;
+            }
+        }
+        self.write_bits(other.pending as u64, other.pending_bits as usize)
+    }
 }
 
 /// Streaming bit reader over a byte slice.
@@ -99,7 +216,7 @@ impl<'a> BitReader<'a> {
                 self.byte_pos += 1;
                 self.pending_bits = 8;
             }
-            let take = self.pending_bits.min(width as u8);
+            let take = self.pending_bits.min(width.min(8) as u8);
             let shift = self.pending_bits - take;
             let mask = ((1 << take) - 1) as u8;
             let part = (self.pending >> shift) & mask;
@@ -114,6 +231,11 @@ impl<'a> BitReader<'a> {
     pub fn bits_consumed(&self) -> usize {
         (self.byte_pos * 8).saturating_sub(self.pending_bits as usize)
     }
+
+    /// Number of bits left in the underlying slice.
+    pub fn bits_remaining(&self) -> usize {
+        (self.bytes.len() - self.byte_pos) * 8 + self.pending_bits as usize
+    }
 }
 
 /// Encode a single integer using Lotus unfolding, returning its payload bits and width.
@@ -263,8 +385,12 @@ pub fn lotus_encode_biguint(
     Ok(writer.into_bytes())
 }
 
-/// Encode an unsigned 64-bit integer using Lotus tiered headers.
-pub fn lotus_encode_u64(value: u64, j_bits: usize, tiers: usize) -> Result<Vec<u8>, LotusError> {
+/// Build the jumpstarter value and the `(bits, width)` chain from payload to outermost tier.
+fn lotus_chain(
+    value: u64,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(u64, Vec<(u64, usize)>), LotusError> {
     if !(1..=8).contains(&j_bits) || tiers == 0 {
         return Err(LotusError::InvalidEncoding);
     }
@@ -286,47 +412,88 @@ pub fn lotus_encode_u64(value: u64, j_bits: usize, tiers: usize) -> Result<Vec<u
     if current_width == 0 || current_width > (1usize << j_bits) {
         return Err(LotusError::JumpstarterOverflow);
     }
-    let jump_val = (current_width - 1) as u64;
+    Ok(((current_width - 1) as u64, chain))
+}
 
-    let mut writer = BitWriter::new();
+/// Append the Lotus codeword for `value` to `writer` without byte-aligning.
+///
+/// Nothing is written if the value cannot be encoded with the given configuration.
+pub fn lotus_write_u64(
+    writer: &mut BitWriter,
+    value: u64,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(), LotusError> {
+    let (jump_val, chain) = lotus_chain(value, j_bits, tiers)?;
     writer.write_bits(jump_val, j_bits)?;
     for (bits, width) in chain.iter().rev() {
         writer.write_bits(*bits, *width)?;
     }
+    Ok(())
+}
+
+/// Encode an unsigned 64-bit integer using Lotus tiered headers.
+pub fn lotus_encode_u64(value: u64, j_bits: usize, tiers: usize) -> Result<Vec<u8>, LotusError> {
+    let mut writer = BitWriter::new();
+    lotus_write_u64(&mut writer, value, j_bits, tiers)?;
     Ok(writer.into_bytes())
 }
 
-/// Decode an unsigned 64-bit integer previously encoded with Lotus.
-pub fn lotus_decode_u64(
-    bytes: &[u8],
+/// Exact length in bits of the Lotus codeword for `value`, before byte padding.
+pub fn lotus_encoded_bits(value: u64, j_bits: usize, tiers: usize) -> Result<usize, LotusError> {
+    let (_, chain) = lotus_chain(value, j_bits, tiers)?;
+    Ok(j_bits + chain.iter().map(|(_, width)| width).sum::<usize>())
+}
+
+/// Read the next Lotus codeword from `reader`, leaving it positioned just past the codeword.
+pub fn lotus_read_u64(
+    reader: &mut BitReader<'_>,
     j_bits: usize,
     tiers: usize,
-) -> Result<(u64, usize), LotusError> {
+) -> Result<u64, LotusError> {
     if !(1..=8).contains(&j_bits) || tiers == 0 {
         return Err(LotusError::InvalidEncoding);
     }
     let max_width = max_width_for_config(j_bits, tiers);
-    let mut reader = BitReader::new(bytes);
-    let start_bits = reader.bits_consumed();
     let jump_val = reader.read_bits(j_bits)? as usize;
     let mut next_width = jump_val + 1;
-    if next_width as u128 > max_width {
+    // No field wider than 64 bits decodes, so it is rejected before it is read.
+    if next_width > 64 || next_width as u128 > max_width {
         return Err(LotusError::ValueTooLarge);
     }
 
     for _ in 0..tiers {
         let tier_payload = reader.read_bits(next_width)?;
         let width_value = lotus_decode_value(tier_payload, next_width)? as usize;
-        if width_value == 0 || width_value as u128 > max_width {
+        if width_value == 0 || width_value > 64 || width_value as u128 > max_width {
             return Err(LotusError::ValueTooLarge);
         }
         next_width = width_value;
     }
 
     let payload = reader.read_bits(next_width)?;
-    let value = lotus_decode_value(payload, next_width)?;
-    let total_bits = reader.bits_consumed().saturating_sub(start_bits);
-    Ok((value, total_bits))
+    lotus_decode_value(payload, next_width)
+}
+
+/// Decode an unsigned 64-bit integer previously encoded with Lotus.
+pub fn lotus_decode_u64(
+    bytes: &[u8],
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(u64, usize), LotusError> {
+    let mut reader = BitReader::new(bytes);
+    let value = lotus_read_u64(&mut reader, j_bits, tiers)?;
+    Ok((value, reader.bits_consumed()))
+}
+
+/// Map a signed integer onto the unsigned range so small magnitudes get short codewords.
+pub fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Inverse of [`zigzag_encode`].
+pub fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
 }
 
 /// Preset configuration: Jumpstarter 2 bits, 1 tier.
@@ -336,6 +503,9 @@ pub const LOTUS_J1D2: (usize, usize) = (1, 2);
 /// Preset configuration: Jumpstarter 3 bits, 1 tier.
 pub const LOTUS_J3D1: (usize, usize) = (3, 1);
 
+/// Configuration used for counts and other metadata inside composite streams; covers all of `u64`.
+pub(crate) const HEADER_CONFIG: (usize, usize) = LOTUS_J3D1;
+
 #[cfg(feature = "small-int-fastpath")]
 pub fn lotus_encode_small(value: u64) -> Result<Vec<u8>, LotusError> {
     if value < 128 {
@@ -402,4 +572,12 @@ mod tests {
         let err = lotus_decode_u64(&[], j_bits, tiers).unwrap_err();
         assert_eq!(err, LotusError::UnexpectedEof);
     }
+
+    #[test]
+    fn wide_fields_rejected_before_reading() {
+        // J3D1: jumpstarter 7 names an 8-bit tier field, and 0b0000_0011 decodes to width 256.
+        let (j_bits, tiers) = LOTUS_J3D1;
+        let err = lotus_decode_u64(&[0b1110_0000, 0b0110_0000], j_bits, tiers).unwrap_err();
+        assert_eq!(err, LotusError::ValueTooLarge);
+    }
 }
@@ -0,0 +1,258 @@
+//! Exhaustive check of a configuration's code up to a bit budget.
+//!
+//! [`verify_config`] walks every jumpstarter, tier and payload value that fits in the budget, as
+//! the decoder reads them, and cross-checks each leaf against the public encoder and decoder: a
+//! codeword must round-trip bit for bit, and a rejected prefix must be rejected by
+//! [`lotus_read_u64`] too. The walk also measures how much of the code space is never used.
+
+use crate::{
+    BitReader, BitWriter, LotusError, lotus_decode_value, lotus_read_u64, lotus_write_u64,
+    max_width_for_config,
+};
+
+/// Largest bit budget [`verify_config`] accepts; the walk visits up to `2^max_bits` leaves.
+pub const MAX_CHECK_BITS: usize = 24;
+
+/// The code space of one configuration, up to a bit budget.
+///
+/// Space is counted in units of `2^-max_bits`, so `used + unused + unexplored` is exactly
+/// `2^max_bits`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigCheck {
+    pub j_bits: usize,
+    pub tiers: usize,
+    pub max_bits: usize,
+    /// Codewords of at most `max_bits` bits; they code exactly the values `0..codewords`.
+    pub codewords: u64,
+    /// Space taken by those codewords: their Kraft sum, scaled.
+    pub used: u64,
+    /// Space no codeword can take: field values the decoder rejects.
+    pub unused: u64,
+    /// Space behind headers whose codewords are longer than `max_bits`.
+    pub unexplored: u64,
+}
+
+impl ConfigCheck {
+    fn fraction(&self, space: u64) -> f64 {
+        space as f64 / (1u64 << self.max_bits) as f64
+    }
+
+    /// Kraft sum of the codewords within the budget.
+    pub fn kraft_sum(&self) -> f64 {
+        self.fraction(self.used)
+    }
+
+    /// `1 - kraft_sum`: unused space plus space the budget did not reach.
+    pub fn kraft_deficit(&self) -> f64 {
+        self.fraction(self.unused + self.unexplored)
+    }
+
+    /// Share of the code space that is provably never used, whatever the budget.
+    pub fn unused_fraction(&self) -> f64 {
+        self.fraction(self.unused)
+    }
+}
+
+struct Walk {
+    j_bits: usize,
+    tiers: usize,
+    max_bits: usize,
+    max_width: u128,
+    /// Last codeword visited, left-aligned, with its length.
+    last: Option<(u64, usize)>,
+    values: Vec<u64>,
+    used: u64,
+    unused: u64,
+    unexplored: u64,
+}
+
+impl Walk {
+    fn fail(&self, bits: u64, len: usize, problem: &str) -> LotusError {
+        LotusError::Message(format!(
+            "J{}D{}: {bits:0len$b}: {problem}",
+            self.j_bits, self.tiers
+        ))
+    }
+
+    fn space(&self, len: usize) -> u64 {
+        1 << (self.max_bits - len)
+    }
+
+    /// Visit every value of the `width`-bit field that follows the `len`-bit prefix `bits`.
+    fn field(
+        &mut self,
+        bits: u64,
+        len: usize,
+        width: usize,
+        tiers_left: usize,
+    ) -> Result<(), LotusError> {
+        if len.saturating_add(width) > self.max_bits {
+            self.unexplored += self.space(len);
+            return Ok(());
+        }
+        let len = len + width;
+        for field in 0..1u64 << width {
+            let bits = bits << width | field;
+            let decoded = lotus_decode_value(field, width);
+            match (tiers_left, decoded) {
+                (0, Ok(value)) => self.codeword(bits, len, value)?,
+                (_, Ok(next)) if next != 0 && u128::from(next) <= self.max_width => {
+                    self.field(bits, len, next as usize, tiers_left - 1)?
+                }
+                _ => self.rejected(bits, len)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn codeword(&mut self, bits: u64, len: usize, value: u64) -> Result<(), LotusError> {
+        let aligned = bits << (64 - len);
+        if let Some((last, last_len)) = self.last {
+            let shared = last_len.min(len);
+            if last >> (64 - shared) == aligned >> (64 - shared) {
+                return Err(self.fail(bits, len, "shares a prefix with the previous codeword"));
+            }
+        }
+        self.last = Some((aligned, len));
+
+        let mut writer = BitWriter::new();
+        lotus_write_u64(&mut writer, value, self.j_bits, self.tiers)
+            .map_err(|err| self.fail(bits, len, &format!("{value} does not encode: {err}")))?;
+        let expected = prefix_bytes(bits, len);
+        if writer.bits_written() != len || writer.into_bytes() != expected {
+            return Err(self.fail(bits, len, &format!("{value} encodes differently")));
+        }
+        let mut reader = BitReader::new(&expected);
+        let decoded = lotus_read_u64(&mut reader, self.j_bits, self.tiers);
+        if decoded != Ok(value) || reader.bits_consumed() != len {
+            return Err(self.fail(bits, len, &format!("does not decode back to {value}")));
+        }
+        self.values.push(value);
+        self.used += self.space(len);
+        Ok(())
+    }
+
+    fn rejected(&mut self, bits: u64, len: usize) -> Result<(), LotusError> {
+        let bytes = prefix_bytes(bits, len);
+        match lotus_read_u64(&mut BitReader::new(&bytes), self.j_bits, self.tiers) {
+            Err(LotusError::UnexpectedEof) | Ok(_) => {
+                return Err(self.fail(bits, len, "is not rejected by the decoder"));
+            }
+            Err(_) => {}
+        }
+        self.unused += self.space(len);
+        Ok(())
+    }
+}
+
+fn prefix_bytes(bits: u64, len: usize) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    writer
+        .write_bits(bits, len)
+        .expect("prefix fits in 64 bits");
+    writer.into_bytes()
+}
+
+/// Enumerate every codeword of at most `max_bits` bits and check the code is sound.
+///
+/// The codewords must be prefix-free, each must be exactly what the encoder writes for its value
+/// and decode back to it, and together they must code a contiguous range starting at zero. Any
+/// violation is a [`LotusError::Message`] naming the offending bits. Invalid configurations and
+/// budgets outside `1..=MAX_CHECK_BITS` are [`LotusError::InvalidEncoding`].
+pub fn verify_config(
+    j_bits: usize,
+    tiers: usize,
+    max_bits: usize,
+) -> Result<ConfigCheck, LotusError> {
+    if !(1..=8).contains(&j_bits) || tiers == 0 || !(1..=MAX_CHECK_BITS).contains(&max_bits) {
+        return Err(LotusError::InvalidEncoding);
+    }
+    let mut walk = Walk {
+        j_bits,
+        tiers,
+        max_bits,
+        max_width: max_width_for_config(j_bits, tiers),
+        last: None,
+        values: Vec::new(),
+        used: 0,
+        unused: 0,
+        unexplored: 0,
+    };
+    if j_bits > max_bits {
+        walk.unexplored = 1 << max_bits;
+    } else {
+        for jump in 0..1u64 << j_bits {
+            let width = jump as usize + 1;
+            if width as u128 > walk.max_width {
+                walk.rejected(jump, j_bits)?;
+            } else {
+                walk.field(jump, j_bits, width, tiers)?;
+            }
+        }
+    }
+
+    walk.values.sort_unstable();
+    if let Some(gap) = (0u64..)
+        .zip(&walk.values)
+        .find(|&(index, &value)| index != value)
+    {
+        return Err(LotusError::Message(format!(
+            "J{j_bits}D{tiers}: codewords within {max_bits} bits skip or repeat value {}",
+            gap.0
+        )));
+    }
+    if walk.used + walk.unused + walk.unexplored != 1 << max_bits {
+        return Err(LotusError::Message(format!(
+            "J{j_bits}D{tiers}: the walk did not cover the code space"
+        )));
+    }
+    Ok(ConfigCheck {
+        j_bits,
+        tiers,
+        max_bits,
+        codewords: walk.values.len() as u64,
+        used: walk.used,
+        unused: walk.unused,
+        unexplored: walk.unexplored,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ANALYSIS_MAX_J_BITS, ANALYSIS_MAX_TIERS, lotus_max_value};
+
+    #[test]
+    fn every_config_is_prefix_free_and_bijective() {
+        for j_bits in 1..=ANALYSIS_MAX_J_BITS {
+            for tiers in 1..=ANALYSIS_MAX_TIERS {
+                let check = verify_config(j_bits, tiers, 14).unwrap();
+                assert_eq!(check.used + check.unused + check.unexplored, 1 << 14);
+                // Width-1 tier fields can never name a usable width, so space is always lost.
+                assert!(check.unused > 0, "J{j_bits}D{tiers}");
+                let max = lotus_max_value(j_bits, tiers).unwrap();
+                assert!(check.codewords <= max.saturating_add(1));
+            }
+        }
+    }
+
+    #[test]
+    fn known_code_space() {
+        // J1D1 is small enough to enumerate completely: values 0..=28, nothing unexplored.
+        let check = verify_config(1, 1, 16).unwrap();
+        assert_eq!(check.codewords, 29);
+        assert_eq!(check.unexplored, 0);
+        // Its jumpstarter `0` names a width-1 tier field, which is never valid.
+        assert!(check.unused_fraction() >= 0.5);
+        assert_eq!(check.kraft_sum() + check.kraft_deficit(), 1.0);
+
+        // J2D1 within 12 bits: payload widths 1..=4 behind a 2-bit tier, 5..=7 behind a 3-bit one.
+        let check = verify_config(2, 1, 12).unwrap();
+        assert_eq!(check.codewords, 29 + (1 << 5) + (1 << 6) + (1 << 7));
+        // The jumpstarter `00` names a width-1 tier field.
+        assert!(check.unused_fraction() >= 0.25);
+        assert_eq!(verify_config(2, 1, 0), Err(LotusError::InvalidEncoding));
+        assert_eq!(verify_config(0, 1, 8), Err(LotusError::InvalidEncoding));
+        assert_eq!(verify_config(9, 1, 8), Err(LotusError::InvalidEncoding));
+    }
+}
@@ -0,0 +1,214 @@
+//! Sequence codecs: how a run of values is transformed before Lotus coding.
+//!
+//! These routines do not record the codec, and only frame-of-reference blocks carry their own
+//! value count; containers store the codec and counts in their own headers and pass them back when
+//! reading.
+
+use crate::frame_of_reference::for_read_block_each;
+use crate::{
+    BitReader, BitWriter, ForReference, HEADER_CONFIG, LotusError, for_write_block, lotus_read_u64,
+    lotus_write_u64, zigzag_decode, zigzag_encode,
+};
+
+/// Transform applied to a sequence before each element is written as a Lotus codeword.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceCodec {
+    /// Each value as its own codeword.
+    #[default]
+    Plain,
+    /// Zigzagged wrapping differences from the previous value, starting from 0.
+    Delta,
+    /// A frame-of-reference block (see [`for_write_block`]) with the minimum as reference.
+    FrameOfReference,
+    /// Runs of equal values: each run is the value, then its length minus one in
+    /// [`LOTUS_J3D1`](crate::LOTUS_J3D1).
+    Rle,
+}
+
+impl SequenceCodec {
+    /// Stable tag for recording the codec in a header.
+    pub fn tag(self) -> u64 {
+        match self {
+            Self::Plain => 0,
+            Self::Delta => 1,
+            Self::FrameOfReference => 2,
+            Self::Rle => 3,
+        }
+    }
+
+    /// Inverse of [`tag`](Self::tag); unknown tags are [`LotusError::InvalidEncoding`].
+    pub fn from_tag(tag: u64) -> Result<Self, LotusError> {
+        match tag {
+            0 => Ok(Self::Plain),
+            1 => Ok(Self::Delta),
+            2 => Ok(Self::FrameOfReference),
+            3 => Ok(Self::Rle),
+            _ => Err(LotusError::InvalidEncoding),
+        }
+    }
+}
+
+/// Append `values` to `writer` using `codec`; element codewords use `(j_bits, tiers)`.
+pub fn sequence_write(
+    writer: &mut BitWriter,
+    values: &[u64],
+    codec: SequenceCodec,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(), LotusError> {
+    match codec {
+        SequenceCodec::Plain => {
+            for &value in values {
+                lotus_write_u64(writer, value, j_bits, tiers)?;
+            }
+            Ok(())
+        }
+        SequenceCodec::Delta => {
+            let mut prev = 0u64;
+            for &value in values {
+                let delta = zigzag_encode(value.wrapping_sub(prev) as i64);
+                lotus_write_u64(writer, delta, j_bits, tiers)?;
+                prev = value;
+            }
+            Ok(())
+        }
+        SequenceCodec::FrameOfReference => {
+            for_write_block(writer, values, ForReference::Min, j_bits, tiers)
+        }
+        SequenceCodec::Rle => {
+            let (hj, hd) = HEADER_CONFIG;
+            for run in values.chunk_by(|a, b| a == b) {
+                lotus_write_u64(writer, run[0], j_bits, tiers)?;
+                lotus_write_u64(writer, run.len() as u64 - 1, hj, hd)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Read `count` values written by [`sequence_write`] with the same codec and configuration.
+pub fn sequence_read(
+    reader: &mut BitReader<'_>,
+    count: usize,
+    codec: SequenceCodec,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u64>, LotusError> {
+    // Each codeword takes at least one bit.
+    let mut values = Vec::with_capacity(count.min(reader.bits_remaining()));
+    sequence_read_each(reader, count, codec, j_bits, tiers, |value, _| {
+        values.push(value)
+    })?;
+    Ok(values)
+}
+
+/// Like [`sequence_read`], but passes each value to `emit` as soon as it is decoded, with the bit
+/// position just after the codeword that completed it.
+///
+/// Values already emitted when an error is returned decoded cleanly, so the caller can tell which
+/// value and which bit range the error belongs to.
+pub(crate) fn sequence_read_each(
+    reader: &mut BitReader<'_>,
+    count: usize,
+    codec: SequenceCodec,
+    j_bits: usize,
+    tiers: usize,
+    mut emit: impl FnMut(u64, usize),
+) -> Result<(), LotusError> {
+    match codec {
+        SequenceCodec::Plain => {
+            for _ in 0..count {
+                let value = lotus_read_u64(reader, j_bits, tiers)?;
+                emit(value, reader.bits_consumed());
+            }
+        }
+        SequenceCodec::Delta => {
+            let mut prev = 0u64;
+            for _ in 0..count {
+                let delta = zigzag_decode(lotus_read_u64(reader, j_bits, tiers)?);
+                prev = prev.wrapping_add(delta as u64);
+                emit(prev, reader.bits_consumed());
+            }
+        }
+        SequenceCodec::FrameOfReference => {
+            if for_read_block_each(reader, j_bits, tiers, emit)? != count {
+                return Err(LotusError::InvalidEncoding);
+            }
+        }
+        SequenceCodec::Rle => {
+            let (hj, hd) = HEADER_CONFIG;
+            let mut remaining = count;
+            while remaining > 0 {
+                let value = lotus_read_u64(reader, j_bits, tiers)?;
+                let run = lotus_read_u64(reader, hj, hd)?;
+                if run >= remaining as u64 {
+                    return Err(LotusError::InvalidEncoding);
+                }
+                for _ in 0..=run {
+                    emit(value, reader.bits_consumed());
+                }
+                remaining -= run as usize + 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1};
+
+    const CODECS: [SequenceCodec; 4] = [
+        SequenceCodec::Plain,
+        SequenceCodec::Delta,
+        SequenceCodec::FrameOfReference,
+        SequenceCodec::Rle,
+    ];
+
+    fn encoded_bits(values: &[u64], codec: SequenceCodec, cfg: (usize, usize)) -> usize {
+        let mut writer = BitWriter::new();
+        sequence_write(&mut writer, values, codec, cfg.0, cfg.1).unwrap();
+        writer.bits_written()
+    }
+
+    #[test]
+    fn round_trip_all_codecs() {
+        let values = [5u64, 3, u64::MAX, 0, 1 << 40, 1 << 40, 7];
+        let (j_bits, tiers) = LOTUS_J3D1;
+        for codec in CODECS {
+            let mut writer = BitWriter::new();
+            sequence_write(&mut writer, &values, codec, j_bits, tiers).unwrap();
+            sequence_write(&mut writer, &[], codec, j_bits, tiers).unwrap();
+            let bytes = writer.into_bytes();
+            let mut reader = BitReader::new(&bytes);
+            let decoded = sequence_read(&mut reader, values.len(), codec, j_bits, tiers).unwrap();
+            assert_eq!(decoded, values, "{codec:?}");
+            assert!(
+                sequence_read(&mut reader, 0, codec, j_bits, tiers)
+                    .unwrap()
+                    .is_empty()
+            );
+            assert_eq!(SequenceCodec::from_tag(codec.tag()).unwrap(), codec);
+        }
+        assert_eq!(SequenceCodec::from_tag(4), Err(LotusError::InvalidEncoding));
+    }
+
+    #[test]
+    fn transforms_shrink_suited_data() {
+        let timestamps: Vec<u64> = (0..500).map(|i| 1_700_000_000 + i * 15).collect();
+        let plain = encoded_bits(&timestamps, SequenceCodec::Plain, LOTUS_J3D1);
+        let delta = encoded_bits(&timestamps, SequenceCodec::Delta, LOTUS_J3D1);
+        assert!(delta * 3 < plain, "delta {delta} vs plain {plain}");
+
+        let clustered: Vec<u64> = (0..500).map(|i| 9_000_000 + (i * 7919) % 100).collect();
+        let plain = encoded_bits(&clustered, SequenceCodec::Plain, LOTUS_J2D1);
+        let frame = encoded_bits(&clustered, SequenceCodec::FrameOfReference, LOTUS_J2D1);
+        assert!(frame * 2 < plain, "FOR {frame} vs plain {plain}");
+
+        let flags: Vec<u64> = (0..500).map(|i| (i / 100) % 2).collect();
+        let plain = encoded_bits(&flags, SequenceCodec::Plain, LOTUS_J2D1);
+        let runs = encoded_bits(&flags, SequenceCodec::Rle, LOTUS_J2D1);
+        assert!(runs * 10 < plain, "RLE {runs} vs plain {plain}");
+    }
+}
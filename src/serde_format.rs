@@ -0,0 +1,726 @@
+//! Compact serde data format: one packed bitstream with Lotus-coded integers and lengths.
+//!
+//! Unsigned integers, sequence/map/string lengths, `char`s, `Option` tags and enum variant
+//! indices are Lotus codewords; signed integers go through zigzag first. `bool` is a single bit
+//! and floats are their raw IEEE-754 bits. Struct field names are not stored, so the format is
+//! not self-describing and `deserialize_any` is unsupported.
+
+use crate::{
+    BitReader, BitWriter, LOTUS_J3D1, LotusError, lotus_read_u64, lotus_write_u64, zigzag_decode,
+    zigzag_encode,
+};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use std::fmt::Display;
+
+impl ser::Error for LotusError {
+    fn custom<T: Display>(msg: T) -> Self {
+        LotusError::Message(msg.to_string())
+    }
+}
+
+impl de::Error for LotusError {
+    fn custom<T: Display>(msg: T) -> Self {
+        LotusError::Message(msg.to_string())
+    }
+}
+
+/// Serialize `value` with the default [`LOTUS_J3D1`] configuration.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, LotusError> {
+    to_vec_with_config(value, LOTUS_J3D1.0, LOTUS_J3D1.1)
+}
+
+/// Serialize `value` using the given `(j_bits, tiers)` for every codeword.
+pub fn to_vec_with_config<T: Serialize + ?Sized>(
+    value: &T,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<Vec<u8>, LotusError> {
+    let mut serializer = Serializer::with_config(j_bits, tiers);
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_bytes())
+}
+
+/// Deserialize a value written by [`to_vec`].
+pub fn from_slice<'de, T: de::Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, LotusError> {
+    from_slice_with_config(bytes, LOTUS_J3D1.0, LOTUS_J3D1.1)
+}
+
+/// Deserialize a value written by [`to_vec_with_config`] with the same configuration.
+pub fn from_slice_with_config<'de, T: de::Deserialize<'de>>(
+    bytes: &'de [u8],
+    j_bits: usize,
+    tiers: usize,
+) -> Result<T, LotusError> {
+    let mut deserializer = Deserializer::with_config(bytes, j_bits, tiers);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// Serde serializer writing into a packed Lotus bitstream.
+#[derive(Debug, Clone)]
+pub struct Serializer {
+    writer: BitWriter,
+    j_bits: usize,
+    tiers: usize,
+}
+
+impl Default for Serializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer {
+    /// Serializer using [`LOTUS_J3D1`], which covers the full `u64` range.
+    pub fn new() -> Self {
+        Self::with_config(LOTUS_J3D1.0, LOTUS_J3D1.1)
+    }
+
+    pub fn with_config(j_bits: usize, tiers: usize) -> Self {
+        Self {
+            writer: BitWriter::new(),
+            j_bits,
+            tiers,
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.writer.into_bytes()
+    }
+
+    fn write_u64(&mut self, value: u64) -> Result<(), LotusError> {
+        lotus_write_u64(&mut self.writer, value, self.j_bits, self.tiers)
+    }
+
+    fn write_len(&mut self, len: Option<usize>) -> Result<(), LotusError> {
+        let len = len.ok_or_else(|| {
+            LotusError::Message("sequence and map lengths must be known up front".into())
+        })?;
+        self.write_u64(len as u64)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), LotusError> {
+        self.write_u64(bytes.len() as u64)?;
+        for &byte in bytes {
+            self.writer.write_bits(byte as u64, 8)?;
+        }
+        Ok(())
+    }
+}
+
+impl ser::Serializer for &mut Serializer {
+    type Ok = ();
+    type Error = LotusError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), LotusError> {
+        self.writer.write_bits(v as u64, 1)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), LotusError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), LotusError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), LotusError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), LotusError> {
+        self.write_u64(zigzag_encode(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), LotusError> {
+        self.serialize_u128(((v << 1) ^ (v >> 127)) as u128)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), LotusError> {
+        self.write_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), LotusError> {
+        self.write_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), LotusError> {
+        self.write_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), LotusError> {
+        self.write_u64(v)
+    }
+
+    /// Written as two codewords, high half first.
+    fn serialize_u128(self, v: u128) -> Result<(), LotusError> {
+        self.write_u64((v >> 64) as u64)?;
+        self.write_u64(v as u64)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), LotusError> {
+        self.writer.write_bits(v.to_bits() as u64, 32)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), LotusError> {
+        self.writer.write_bits(v.to_bits(), 64)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), LotusError> {
+        self.write_u64(v as u64)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), LotusError> {
+        self.write_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), LotusError> {
+        self.write_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<(), LotusError> {
+        self.write_u64(0)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), LotusError> {
+        self.write_u64(1)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), LotusError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), LotusError> {
+        self.write_u64(variant_index as u64)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), LotusError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), LotusError> {
+        self.write_u64(variant_index as u64)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, LotusError> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, LotusError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, LotusError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, LotusError> {
+        self.write_u64(variant_index as u64)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, LotusError> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, LotusError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, LotusError> {
+        self.write_u64(variant_index as u64)?;
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut Serializer {
+    type Ok = ();
+    type Error = LotusError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LotusError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut Serializer {
+    type Ok = ();
+    type Error = LotusError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LotusError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut Serializer {
+    type Ok = ();
+    type Error = LotusError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LotusError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut Serializer {
+    type Ok = ();
+    type Error = LotusError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LotusError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut Serializer {
+    type Ok = ();
+    type Error = LotusError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), LotusError> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LotusError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Serializer {
+    type Ok = ();
+    type Error = LotusError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), LotusError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Serializer {
+    type Ok = ();
+    type Error = LotusError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), LotusError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+}
+
+/// Serde deserializer reading from a packed Lotus bitstream.
+#[derive(Debug, Clone)]
+pub struct Deserializer<'de> {
+    reader: BitReader<'de>,
+    j_bits: usize,
+    tiers: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Deserializer using [`LOTUS_J3D1`], matching [`Serializer::new`].
+    pub fn from_slice(bytes: &'de [u8]) -> Self {
+        Self::with_config(bytes, LOTUS_J3D1.0, LOTUS_J3D1.1)
+    }
+
+    pub fn with_config(bytes: &'de [u8], j_bits: usize, tiers: usize) -> Self {
+        Self {
+            reader: BitReader::new(bytes),
+            j_bits,
+            tiers,
+        }
+    }
+
+    /// Check that only the final byte's zero padding is left unread.
+    pub fn end(&mut self) -> Result<(), LotusError> {
+        let remaining = self.reader.bits_remaining();
+        if remaining >= 8 || self.reader.read_bits(remaining)? != 0 {
+            return Err(LotusError::Message("trailing data after value".into()));
+        }
+        Ok(())
+    }
+
+    fn read_u64(&mut self) -> Result<u64, LotusError> {
+        lotus_read_u64(&mut self.reader, self.j_bits, self.tiers)
+    }
+
+    fn read_u128(&mut self) -> Result<u128, LotusError> {
+        let high = self.read_u64()? as u128;
+        Ok((high << 64) | self.read_u64()? as u128)
+    }
+
+    fn read_len(&mut self) -> Result<usize, LotusError> {
+        usize::try_from(self.read_u64()?).map_err(|_| LotusError::ValueTooLarge)
+    }
+
+    fn read_narrow<T: TryFrom<u64>>(&mut self) -> Result<T, LotusError> {
+        T::try_from(self.read_u64()?).map_err(|_| LotusError::ValueTooLarge)
+    }
+
+    fn read_signed<T: TryFrom<i64>>(&mut self) -> Result<T, LotusError> {
+        T::try_from(zigzag_decode(self.read_u64()?)).map_err(|_| LotusError::ValueTooLarge)
+    }
+
+    fn read_byte_buf(&mut self) -> Result<Vec<u8>, LotusError> {
+        let len = self.read_len()?;
+        if len > self.reader.bits_remaining() / 8 {
+            return Err(LotusError::UnexpectedEof);
+        }
+        (0..len)
+            .map(|_| Ok(self.reader.read_bits(8)? as u8))
+            .collect()
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = LotusError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, LotusError> {
+        Err(LotusError::Message(
+            "the lotus format is not self-describing".into(),
+        ))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_bool(self.reader.read_bits(1)? == 1)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_i8(self.read_signed()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_i16(self.read_signed()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_i32(self.read_signed()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_i64(self.read_signed()?)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        let zigzag = self.read_u128()?;
+        visitor.visit_i128(((zigzag >> 1) as i128) ^ -((zigzag & 1) as i128))
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_u8(self.read_narrow()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_u16(self.read_narrow()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_u32(self.read_narrow()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_u64(self.read_u64()?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_u128(self.read_u128()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_f32(f32::from_bits(self.reader.read_bits(32)? as u32))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_f64(f64::from_bits(self.reader.read_bits(64)?))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        let code = self.read_narrow::<u32>()?;
+        visitor.visit_char(char::from_u32(code).ok_or(LotusError::InvalidEncoding)?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        let bytes = self.read_byte_buf()?;
+        visitor.visit_string(String::from_utf8(bytes).map_err(|_| LotusError::InvalidEncoding)?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_byte_buf(self.read_byte_buf()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        match self.read_u64()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            _ => Err(LotusError::InvalidEncoding),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, LotusError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, LotusError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        let len = self.read_len()?;
+        visitor.visit_seq(Counted {
+            de: self,
+            remaining: len,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, LotusError> {
+        visitor.visit_seq(Counted {
+            de: self,
+            remaining: len,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, LotusError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        let len = self.read_len()?;
+        visitor.visit_map(Counted {
+            de: self,
+            remaining: len,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, LotusError> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, LotusError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LotusError> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, LotusError> {
+        Err(LotusError::Message(
+            "the lotus format cannot skip unknown values".into(),
+        ))
+    }
+}
+
+/// Sequence or map access with a known element count.
+struct Counted<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    remaining: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Counted<'_, 'de> {
+    type Error = LotusError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, LotusError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Counted<'_, 'de> {
+    type Error = LotusError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, LotusError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, LotusError> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = LotusError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), LotusError> {
+        let index = self.read_narrow::<u32>()?;
+        let deserializer: de::value::U32Deserializer<LotusError> = index.into_deserializer();
+        let value = seed.deserialize(deserializer)?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = LotusError;
+
+    fn unit_variant(self) -> Result<(), LotusError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, LotusError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, LotusError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, LotusError> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
@@ -0,0 +1,159 @@
+//! x86_64 bit-extraction kernels for the interleaved decoder.
+//!
+//! Both kernels follow the contract of [`ExtractKernel`]: every field lies inside the unaligned
+//! big-endian 64-bit window starting at byte `pos / 8`.
+
+use crate::interleave::{ExtractKernel, KERNEL_MAX_WIDTH};
+use std::arch::x86_64::*;
+
+/// Pick the widest kernel the running CPU supports.
+pub(crate) fn select_kernel() -> ExtractKernel {
+    if is_x86_feature_detected!("avx2") {
+        extract_avx2_checked
+    } else {
+        extract_sse2
+    }
+}
+
+fn check_contract(data: &[u8], pos: &[usize; 4], width: &[usize; 4]) {
+    for lane in 0..4 {
+        assert!(pos[lane] / 8 + 8 <= data.len());
+        assert!((1..=KERNEL_MAX_WIDTH).contains(&width[lane]));
+    }
+}
+
+/// Shift the low and high 64-bit halves of `words` by different counts.
+///
+/// SSE2 shifts every lane by the same count, so shift twice and keep one half of each result.
+#[target_feature(enable = "sse2")]
+fn shift_halves(words: __m128i, lo: usize, hi: usize, left: bool) -> __m128i {
+    let shift = |count: usize| {
+        let count = _mm_cvtsi64_si128(count as i64);
+        if left {
+            _mm_sll_epi64(words, count)
+        } else {
+            _mm_srl_epi64(words, count)
+        }
+    };
+    _mm_castpd_si128(_mm_move_sd(
+        _mm_castsi128_pd(shift(hi)),
+        _mm_castsi128_pd(shift(lo)),
+    ))
+}
+
+pub(crate) fn extract_sse2(data: &[u8], pos: &[usize; 4], width: &[usize; 4]) -> [u64; 4] {
+    check_contract(data, pos, width);
+    // SAFETY: SSE2 is part of the x86_64 baseline, so no runtime detection is needed.
+    unsafe { extract_sse2_pairs(data, pos, width) }
+}
+
+#[target_feature(enable = "sse2")]
+fn extract_sse2_pairs(data: &[u8], pos: &[usize; 4], width: &[usize; 4]) -> [u64; 4] {
+    let window = |lane: usize| {
+        let offset = pos[lane] / 8;
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[offset..offset + 8]);
+        u64::from_be_bytes(bytes) as i64
+    };
+    let mut out = [0u64; 4];
+    for lo in [0, 2] {
+        let hi = lo + 1;
+        let words = _mm_set_epi64x(window(hi), window(lo));
+        let words = shift_halves(words, pos[lo] % 8, pos[hi] % 8, true);
+        let fields = shift_halves(words, 64 - width[lo], 64 - width[hi], false);
+        out[lo] = _mm_cvtsi128_si64(fields) as u64;
+        out[hi] = _mm_cvtsi128_si64(_mm_unpackhi_epi64(fields, fields)) as u64;
+    }
+    out
+}
+
+fn extract_avx2_checked(data: &[u8], pos: &[usize; 4], width: &[usize; 4]) -> [u64; 4] {
+    check_contract(data, pos, width);
+    // SAFETY: this kernel is only handed out by `select_kernel` after AVX2 was detected, and
+    // `check_contract` keeps every gathered window inside `data`.
+    unsafe { extract_avx2(data, pos, width) }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn extract_avx2(data: &[u8], pos: &[usize; 4], width: &[usize; 4]) -> [u64; 4] {
+    let offsets = _mm256_set_epi64x(
+        (pos[3] / 8) as i64,
+        (pos[2] / 8) as i64,
+        (pos[1] / 8) as i64,
+        (pos[0] / 8) as i64,
+    );
+    // SAFETY: the caller guarantees `offset + 8 <= data.len()` for every lane.
+    let words = unsafe { _mm256_i64gather_epi64::<1>(data.as_ptr().cast::<i64>(), offsets) };
+    let big_endian = _mm256_setr_epi8(
+        7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13,
+        12, 11, 10, 9, 8,
+    );
+    let words = _mm256_shuffle_epi8(words, big_endian);
+    let misalign = _mm256_set_epi64x(
+        (pos[3] % 8) as i64,
+        (pos[2] % 8) as i64,
+        (pos[1] % 8) as i64,
+        (pos[0] % 8) as i64,
+    );
+    let discard = _mm256_set_epi64x(
+        (64 - width[3]) as i64,
+        (64 - width[2]) as i64,
+        (64 - width[1]) as i64,
+        (64 - width[0]) as i64,
+    );
+    let fields = _mm256_srlv_epi64(_mm256_sllv_epi64(words, misalign), discard);
+    let mut out = [0u64; 4];
+    // SAFETY: `out` is exactly 32 bytes and `storeu` has no alignment requirement.
+    unsafe { _mm256_storeu_si256(out.as_mut_ptr().cast::<__m256i>(), fields) };
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interleave::extract_scalar;
+
+    fn cases() -> Vec<([usize; 4], [usize; 4])> {
+        let mut out = Vec::new();
+        for shift in 0..8 {
+            for width in [1usize, 7, 8, 9, 31, 32, 33, 55, 56] {
+                out.push((
+                    [shift, 8 + (shift + 3) % 8, 17 * shift, 3 + shift],
+                    [width, 57 - width, (width % 13) + 1, width],
+                ));
+            }
+        }
+        out
+    }
+
+    fn data() -> Vec<u8> {
+        (0..256u32)
+            .map(|i| (i.wrapping_mul(167) ^ 0x5a) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn sse2_matches_scalar() {
+        let data = data();
+        for (pos, width) in cases() {
+            assert_eq!(
+                extract_sse2(&data, &pos, &width),
+                extract_scalar(&data, &pos, &width)
+            );
+        }
+    }
+
+    #[test]
+    fn avx2_matches_scalar() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+        let data = data();
+        for (pos, width) in cases() {
+            assert_eq!(
+                extract_avx2_checked(&data, &pos, &width),
+                extract_scalar(&data, &pos, &width)
+            );
+        }
+    }
+}
@@ -0,0 +1,605 @@
+//! `LotusEncode` / `LotusDecode`: schema-free encoding of std types into packed bitstreams.
+//!
+//! Unsigned integers are Lotus codewords and signed integers are zigzagged first; 128-bit
+//! integers take two codewords, high half first. Lengths of `Vec`, `String` and maps, `Option`
+//! tags and `char`s are Lotus codewords too, so composite values need no external schema.
+//! `bool` is a single bit and fixed-size arrays and tuples store no length at all.
+
+use crate::{
+    BitReader, BitWriter, LotusError, lotus_read_u64, lotus_write_u64, zigzag_decode, zigzag_encode,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+/// Types that can append themselves to a Lotus bitstream.
+pub trait LotusEncode {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError>;
+
+    /// Encode `self` on its own into a byte-padded buffer.
+    fn lotus_encode_to_vec(&self, j_bits: usize, tiers: usize) -> Result<Vec<u8>, LotusError> {
+        let mut writer = BitWriter::new();
+        self.lotus_encode(&mut writer, j_bits, tiers)?;
+        Ok(writer.into_bytes())
+    }
+}
+
+/// Types that can be read back from a Lotus bitstream.
+pub trait LotusDecode: Sized {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError>;
+
+    /// Decode a value produced by [`LotusEncode::lotus_encode_to_vec`].
+    fn lotus_decode_from_slice(
+        bytes: &[u8],
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        Self::lotus_decode(&mut BitReader::new(bytes), j_bits, tiers)
+    }
+}
+
+/// Most bytes a decoded length preallocates, as serde does with its cautious size hint; longer
+/// collections grow as their elements are actually read.
+const MAX_PREALLOC_BYTES: usize = 1 << 20;
+
+/// Read a length or count, and the capacity to preallocate for that many `T`s.
+fn read_len<T>(
+    reader: &mut BitReader<'_>,
+    j_bits: usize,
+    tiers: usize,
+) -> Result<(usize, usize), LotusError> {
+    let len = usize::try_from(lotus_read_u64(reader, j_bits, tiers)?)
+        .map_err(|_| LotusError::ValueTooLarge)?;
+    let budget = MAX_PREALLOC_BYTES / size_of::<T>().max(1);
+    Ok((len, len.min(reader.bits_remaining()).min(budget)))
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),*) => {$(
+        impl LotusEncode for $ty {
+            fn lotus_encode(
+                &self,
+                writer: &mut BitWriter,
+                j_bits: usize,
+                tiers: usize,
+            ) -> Result<(), LotusError> {
+                lotus_write_u64(writer, *self as u64, j_bits, tiers)
+            }
+        }
+
+        impl LotusDecode for $ty {
+            fn lotus_decode(
+                reader: &mut BitReader<'_>,
+                j_bits: usize,
+                tiers: usize,
+            ) -> Result<Self, LotusError> {
+                <$ty>::try_from(lotus_read_u64(reader, j_bits, tiers)?)
+                    .map_err(|_| LotusError::ValueTooLarge)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($ty:ty),*) => {$(
+        impl LotusEncode for $ty {
+            fn lotus_encode(
+                &self,
+                writer: &mut BitWriter,
+                j_bits: usize,
+                tiers: usize,
+            ) -> Result<(), LotusError> {
+                lotus_write_u64(writer, zigzag_encode(*self as i64), j_bits, tiers)
+            }
+        }
+
+        impl LotusDecode for $ty {
+            fn lotus_decode(
+                reader: &mut BitReader<'_>,
+                j_bits: usize,
+                tiers: usize,
+            ) -> Result<Self, LotusError> {
+                <$ty>::try_from(zigzag_decode(lotus_read_u64(reader, j_bits, tiers)?))
+                    .map_err(|_| LotusError::ValueTooLarge)
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, usize);
+impl_signed!(i8, i16, i32, i64, isize);
+
+impl LotusEncode for u128 {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        lotus_write_u64(writer, (*self >> 64) as u64, j_bits, tiers)?;
+        lotus_write_u64(writer, *self as u64, j_bits, tiers)
+    }
+}
+
+impl LotusDecode for u128 {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        let high = lotus_read_u64(reader, j_bits, tiers)? as u128;
+        Ok((high << 64) | lotus_read_u64(reader, j_bits, tiers)? as u128)
+    }
+}
+
+impl LotusEncode for i128 {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        (((*self << 1) ^ (*self >> 127)) as u128).lotus_encode(writer, j_bits, tiers)
+    }
+}
+
+impl LotusDecode for i128 {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        let zigzag = u128::lotus_decode(reader, j_bits, tiers)?;
+        Ok(((zigzag >> 1) as i128) ^ -((zigzag & 1) as i128))
+    }
+}
+
+impl LotusEncode for bool {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        _j_bits: usize,
+        _tiers: usize,
+    ) -> Result<(), LotusError> {
+        writer.write_bits(*self as u64, 1)
+    }
+}
+
+impl LotusDecode for bool {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        _j_bits: usize,
+        _tiers: usize,
+    ) -> Result<Self, LotusError> {
+        Ok(reader.read_bits(1)? == 1)
+    }
+}
+
+impl LotusEncode for char {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        lotus_write_u64(writer, *self as u64, j_bits, tiers)
+    }
+}
+
+impl LotusDecode for char {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        let code = u32::lotus_decode(reader, j_bits, tiers)?;
+        char::from_u32(code).ok_or(LotusError::InvalidEncoding)
+    }
+}
+
+impl LotusEncode for () {
+    fn lotus_encode(
+        &self,
+        _writer: &mut BitWriter,
+        _j_bits: usize,
+        _tiers: usize,
+    ) -> Result<(), LotusError> {
+        Ok(())
+    }
+}
+
+impl LotusDecode for () {
+    fn lotus_decode(
+        _reader: &mut BitReader<'_>,
+        _j_bits: usize,
+        _tiers: usize,
+    ) -> Result<Self, LotusError> {
+        Ok(())
+    }
+}
+
+impl<T: LotusEncode + ?Sized> LotusEncode for &T {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        (**self).lotus_encode(writer, j_bits, tiers)
+    }
+}
+
+impl<T: LotusEncode + ?Sized> LotusEncode for Box<T> {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        (**self).lotus_encode(writer, j_bits, tiers)
+    }
+}
+
+impl<T: LotusDecode> LotusDecode for Box<T> {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        T::lotus_decode(reader, j_bits, tiers).map(Box::new)
+    }
+}
+
+impl<T: LotusEncode> LotusEncode for Option<T> {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        match self {
+            None => lotus_write_u64(writer, 0, j_bits, tiers),
+            Some(value) => {
+                lotus_write_u64(writer, 1, j_bits, tiers)?;
+                value.lotus_encode(writer, j_bits, tiers)
+            }
+        }
+    }
+}
+
+impl<T: LotusDecode> LotusDecode for Option<T> {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        match lotus_read_u64(reader, j_bits, tiers)? {
+            0 => Ok(None),
+            1 => T::lotus_decode(reader, j_bits, tiers).map(Some),
+            _ => Err(LotusError::InvalidEncoding),
+        }
+    }
+}
+
+impl<T: LotusEncode> LotusEncode for [T] {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        lotus_write_u64(writer, self.len() as u64, j_bits, tiers)?;
+        for item in self {
+            item.lotus_encode(writer, j_bits, tiers)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: LotusEncode> LotusEncode for Vec<T> {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        self.as_slice().lotus_encode(writer, j_bits, tiers)
+    }
+}
+
+impl<T: LotusDecode> LotusDecode for Vec<T> {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        let (len, capacity) = read_len::<T>(reader, j_bits, tiers)?;
+        let mut items = Vec::with_capacity(capacity);
+        for _ in 0..len {
+            items.push(T::lotus_decode(reader, j_bits, tiers)?);
+        }
+        Ok(items)
+    }
+}
+
+impl LotusEncode for str {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        lotus_write_u64(writer, self.len() as u64, j_bits, tiers)?;
+        for &byte in self.as_bytes() {
+            writer.write_bits(byte as u64, 8)?;
+        }
+        Ok(())
+    }
+}
+
+impl LotusEncode for String {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        self.as_str().lotus_encode(writer, j_bits, tiers)
+    }
+}
+
+impl LotusDecode for String {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        let (len, _) = read_len::<u8>(reader, j_bits, tiers)?;
+        if len > reader.bits_remaining() / 8 {
+            return Err(LotusError::UnexpectedEof);
+        }
+        let bytes = (0..len)
+            .map(|_| Ok(reader.read_bits(8)? as u8))
+            .collect::<Result<Vec<u8>, LotusError>>()?;
+        String::from_utf8(bytes).map_err(|_| LotusError::InvalidEncoding)
+    }
+}
+
+impl<T: LotusEncode, const N: usize> LotusEncode for [T; N] {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        for item in self {
+            item.lotus_encode(writer, j_bits, tiers)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: LotusDecode, const N: usize> LotusDecode for [T; N] {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        let mut items = Vec::with_capacity(N);
+        for _ in 0..N {
+            items.push(T::lotus_decode(reader, j_bits, tiers)?);
+        }
+        items.try_into().map_err(|_| LotusError::InvalidEncoding)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: LotusEncode),+> LotusEncode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn lotus_encode(
+                &self,
+                writer: &mut BitWriter,
+                j_bits: usize,
+                tiers: usize,
+            ) -> Result<(), LotusError> {
+                let ($($name,)+) = self;
+                $($name.lotus_encode(writer, j_bits, tiers)?;)+
+                Ok(())
+            }
+        }
+
+        impl<$($name: LotusDecode),+> LotusDecode for ($($name,)+) {
+            fn lotus_decode(
+                reader: &mut BitReader<'_>,
+                j_bits: usize,
+                tiers: usize,
+            ) -> Result<Self, LotusError> {
+                Ok(($($name::lotus_decode(reader, j_bits, tiers)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
+
+impl<K: LotusEncode, V: LotusEncode> LotusEncode for BTreeMap<K, V> {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        lotus_write_u64(writer, self.len() as u64, j_bits, tiers)?;
+        for (key, value) in self {
+            key.lotus_encode(writer, j_bits, tiers)?;
+            value.lotus_encode(writer, j_bits, tiers)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K: LotusDecode + Ord, V: LotusDecode> LotusDecode for BTreeMap<K, V> {
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        let (len, _) = read_len::<(K, V)>(reader, j_bits, tiers)?;
+        let mut map = BTreeMap::new();
+        for _ in 0..len {
+            let key = K::lotus_decode(reader, j_bits, tiers)?;
+            let value = V::lotus_decode(reader, j_bits, tiers)?;
+            if map.insert(key, value).is_some() {
+                return Err(LotusError::InvalidEncoding);
+            }
+        }
+        Ok(map)
+    }
+}
+
+/// Entries are written in the map's iteration order, so equal maps may encode differently.
+impl<K: LotusEncode, V: LotusEncode, S> LotusEncode for HashMap<K, V, S> {
+    fn lotus_encode(
+        &self,
+        writer: &mut BitWriter,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<(), LotusError> {
+        lotus_write_u64(writer, self.len() as u64, j_bits, tiers)?;
+        for (key, value) in self {
+            key.lotus_encode(writer, j_bits, tiers)?;
+            value.lotus_encode(writer, j_bits, tiers)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K, V, S> LotusDecode for HashMap<K, V, S>
+where
+    K: LotusDecode + Eq + Hash,
+    V: LotusDecode,
+    S: BuildHasher + Default,
+{
+    fn lotus_decode(
+        reader: &mut BitReader<'_>,
+        j_bits: usize,
+        tiers: usize,
+    ) -> Result<Self, LotusError> {
+        let (len, capacity) = read_len::<(K, V)>(reader, j_bits, tiers)?;
+        let mut map = HashMap::with_capacity_and_hasher(capacity, S::default());
+        for _ in 0..len {
+            let key = K::lotus_decode(reader, j_bits, tiers)?;
+            let value = V::lotus_decode(reader, j_bits, tiers)?;
+            if map.insert(key, value).is_some() {
+                return Err(LotusError::InvalidEncoding);
+            }
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LOTUS_J2D1, LOTUS_J3D1};
+
+    fn round_trip<T: LotusEncode + LotusDecode + PartialEq + std::fmt::Debug>(value: T) {
+        let (j_bits, tiers) = LOTUS_J3D1;
+        let bytes = value.lotus_encode_to_vec(j_bits, tiers).unwrap();
+        assert_eq!(
+            T::lotus_decode_from_slice(&bytes, j_bits, tiers).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn primitives_round_trip() {
+        round_trip(u8::MAX);
+        round_trip(u16::MAX);
+        round_trip(u32::MAX);
+        round_trip(u64::MAX);
+        round_trip(usize::MAX);
+        round_trip(u128::MAX);
+        round_trip(i8::MIN);
+        round_trip(i16::MIN);
+        round_trip(i32::MIN);
+        round_trip(i64::MIN);
+        round_trip(isize::MAX);
+        round_trip(i128::MIN);
+        round_trip(-1i128);
+        round_trip(true);
+        round_trip('🪷');
+        round_trip(());
+    }
+
+    #[test]
+    fn composites_round_trip() {
+        round_trip(Some(vec![Some(3u8), None]));
+        round_trip(String::from("lotus ✿"));
+        round_trip([[1u16, 2], [3, 4], [5, 6]]);
+        round_trip((1u8, -2i16, 'x', (true, String::new())));
+        round_trip(BTreeMap::from([(1u32, vec![-1i64]), (7, vec![])]));
+        round_trip(HashMap::<String, u64>::from([
+            ("a".into(), 1),
+            ("b".into(), 2),
+        ]));
+        round_trip(Box::new(Option::<u8>::None));
+    }
+
+    #[test]
+    fn sequence_with_small_config() {
+        let (j_bits, tiers) = LOTUS_J2D1;
+        let values: Vec<u16> = (0..100).collect();
+        let bytes = values.lotus_encode_to_vec(j_bits, tiers).unwrap();
+        assert_eq!(
+            Vec::<u16>::lotus_decode_from_slice(&bytes, j_bits, tiers).unwrap(),
+            values
+        );
+        assert!(bytes.len() < values.len() * 2);
+    }
+
+    #[test]
+    fn invalid_inputs_rejected() {
+        let (j_bits, tiers) = LOTUS_J3D1;
+        let surrogate = 0xD800u32.lotus_encode_to_vec(j_bits, tiers).unwrap();
+        assert_eq!(
+            char::lotus_decode_from_slice(&surrogate, j_bits, tiers).unwrap_err(),
+            LotusError::InvalidEncoding
+        );
+        let wide = 300u16.lotus_encode_to_vec(j_bits, tiers).unwrap();
+        assert_eq!(
+            u8::lotus_decode_from_slice(&wide, j_bits, tiers).unwrap_err(),
+            LotusError::ValueTooLarge
+        );
+        let duplicate = vec![(1u8, 2u8), (1, 3)]
+            .lotus_encode_to_vec(j_bits, tiers)
+            .unwrap();
+        assert_eq!(
+            BTreeMap::<u8, u8>::lotus_decode_from_slice(&duplicate, j_bits, tiers).unwrap_err(),
+            LotusError::InvalidEncoding
+        );
+        let text = String::from("truncated")
+            .lotus_encode_to_vec(j_bits, tiers)
+            .unwrap();
+        assert_eq!(
+            String::lotus_decode_from_slice(&text[..4], j_bits, tiers).unwrap_err(),
+            LotusError::UnexpectedEof
+        );
+    }
+}
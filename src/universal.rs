@@ -0,0 +1,505 @@
+//! `IntegerCodec`: one bit-level interface over Lotus and the reference codes it is compared with.
+//!
+//! Each codec maps `u64` to prefix-free codewords on a [`BitWriter`] and reads them back strictly
+//! from a [`BitReader`]. All of `u64` is covered except by [`GolombRice`], which rejects values
+//! whose quotient exceeds [`GOLOMB_RICE_MAX_QUOTIENT`], and by [`LotusCodec`] configurations with
+//! a smaller range; both report [`LotusError::ValueTooLarge`]. Codes defined on positive integers
+//! (Elias gamma, delta and omega, Fibonacci) code `v + 1`, as [`Analysis`](crate::Analysis) does.
+//! A failed decode leaves the reader somewhere inside the bad codeword.
+
+use crate::leb128::{leb128_decode_with, leb128_encode_with};
+use crate::{
+    BitReader, BitWriter, LotusError, lotus_encoded_bits, lotus_read_u64, lotus_write_u64,
+};
+
+/// Largest quotient [`GolombRice`] codes in unary; larger ones are rejected rather than written
+/// as tens of thousands of bits.
+pub const GOLOMB_RICE_MAX_QUOTIENT: u64 = 1 << 16;
+
+/// A prefix-free code for `u64` values over a bitstream.
+pub trait IntegerCodec {
+    /// Short name for reports, such as `elias-delta` or `rice-4`.
+    fn name(&self) -> String;
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError>;
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError>;
+
+    /// Exact length of the codeword for `value`, in bits.
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError>;
+}
+
+/// Lotus with a fixed `(J, d)` configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LotusCodec {
+    pub j_bits: usize,
+    pub tiers: usize,
+}
+
+impl LotusCodec {
+    pub fn new(j_bits: usize, tiers: usize) -> Self {
+        Self { j_bits, tiers }
+    }
+}
+
+impl IntegerCodec for LotusCodec {
+    fn name(&self) -> String {
+        format!("J{}D{}", self.j_bits, self.tiers)
+    }
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError> {
+        lotus_write_u64(writer, value, self.j_bits, self.tiers)
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError> {
+        lotus_read_u64(reader, self.j_bits, self.tiers)
+    }
+
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError> {
+        lotus_encoded_bits(value, self.j_bits, self.tiers)
+    }
+}
+
+/// Unsigned LEB128 written a byte at a time, with the same strictness as
+/// [`leb128_read`](crate::leb128_read).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Leb128;
+
+impl IntegerCodec for Leb128 {
+    fn name(&self) -> String {
+        "leb128".to_string()
+    }
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError> {
+        leb128_encode_with(value, |byte| writer.write_bits(byte as u64, 8))
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError> {
+        leb128_decode_with(|| Ok(reader.read_bits(8)? as u8))
+    }
+
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError> {
+        Ok(crate::leb128_len(value) * 8)
+    }
+}
+
+/// Elias gamma: `n = v + 1` as `bit_length(n) - 1` zeros followed by `n` in binary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EliasGamma;
+
+/// Elias delta: the bit length of `n = v + 1` in Elias gamma, then `n` without its leading one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EliasDelta;
+
+/// Elias omega: `n = v + 1` preceded by its recursively coded lengths and ended by a zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EliasOmega;
+
+/// Fibonacci coding: the Zeckendorf digits of `n = v + 1`, least significant first, then a one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fibonacci;
+
+/// Exponential-Golomb of order `k`: Elias gamma of `v + 2^k` with its `k` low bits left uncounted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpGolomb {
+    /// Order, at most 63; order 0 is Elias gamma.
+    pub k: u32,
+}
+
+/// Golomb-Rice with divisor `2^k`: the quotient in unary (ones ended by a zero), then `k` low bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GolombRice {
+    /// Parameter, at most 63.
+    pub k: u32,
+}
+
+fn bit_length(n: u128) -> usize {
+    128 - n.leading_zeros() as usize
+}
+
+/// Write the low `width` bits of `value`, for `width` up to 128.
+fn write_wide(writer: &mut BitWriter, value: u128, width: usize) -> Result<(), LotusError> {
+    if width > 64 {
+        writer.write_bits((value >> 64) as u64, width - 64)?;
+        writer.write_bits(value as u64, 64)
+    } else {
+        writer.write_bits(value as u64, width)
+    }
+}
+
+fn read_wide(reader: &mut BitReader<'_>, width: usize) -> Result<u128, LotusError> {
+    if width > 64 {
+        let high = u128::from(reader.read_bits(width - 64)?);
+        Ok(high << 64 | u128::from(reader.read_bits(64)?))
+    } else {
+        Ok(u128::from(reader.read_bits(width)?))
+    }
+}
+
+/// Count the `bit`s before the first opposite bit, consuming it too; more than `limit` is invalid.
+fn read_run(reader: &mut BitReader<'_>, bit: u64, limit: u64) -> Result<u64, LotusError> {
+    let mut run = 0;
+    while reader.read_bits(1)? == bit {
+        run += 1;
+        if run > limit {
+            return Err(LotusError::InvalidEncoding);
+        }
+    }
+    Ok(run)
+}
+
+/// `n - 1` for a decoded positive `n`, which may be one past `u64::MAX`.
+fn shifted_down(n: u128) -> Result<u64, LotusError> {
+    u64::try_from(n - 1).map_err(|_| LotusError::ValueTooLarge)
+}
+
+fn gamma_write(writer: &mut BitWriter, n: u128) -> Result<(), LotusError> {
+    let len = bit_length(n);
+    writer.write_bits(0, len - 1)?;
+    write_wide(writer, n, len)
+}
+
+/// Read a gamma-coded positive integer of at most `max_len` bits.
+fn gamma_read(reader: &mut BitReader<'_>, max_len: usize) -> Result<u128, LotusError> {
+    let zeros = read_run(reader, 0, max_len as u64 - 1)? as usize;
+    Ok(1 << zeros | read_wide(reader, zeros)?)
+}
+
+pub(crate) fn elias_gamma_bits(value: u64) -> usize {
+    2 * bit_length(u128::from(value) + 1) - 1
+}
+
+pub(crate) fn elias_delta_bits(value: u64) -> usize {
+    let len = bit_length(u128::from(value) + 1);
+    len - 1 + 2 * bit_length(len as u128) - 1
+}
+
+impl IntegerCodec for EliasGamma {
+    fn name(&self) -> String {
+        "elias-gamma".to_string()
+    }
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError> {
+        gamma_write(writer, u128::from(value) + 1)
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError> {
+        shifted_down(gamma_read(reader, 65)?)
+    }
+
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError> {
+        Ok(elias_gamma_bits(value))
+    }
+}
+
+impl IntegerCodec for EliasDelta {
+    fn name(&self) -> String {
+        "elias-delta".to_string()
+    }
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError> {
+        let n = u128::from(value) + 1;
+        let len = bit_length(n);
+        gamma_write(writer, len as u128)?;
+        write_wide(writer, n, len - 1)
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError> {
+        // Lengths run up to 65, which takes 7 bits.
+        let len = gamma_read(reader, 7)? as usize;
+        if len > 65 {
+            return Err(LotusError::InvalidEncoding);
+        }
+        shifted_down(1 << (len - 1) | read_wide(reader, len - 1)?)
+    }
+
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError> {
+        Ok(elias_delta_bits(value))
+    }
+}
+
+/// Omega groups of `n`, outermost length first.
+fn omega_groups(mut n: u128) -> Vec<u128> {
+    let mut groups = Vec::new();
+    while n > 1 {
+        groups.push(n);
+        n = bit_length(n) as u128 - 1;
+    }
+    groups.reverse();
+    groups
+}
+
+impl IntegerCodec for EliasOmega {
+    fn name(&self) -> String {
+        "elias-omega".to_string()
+    }
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError> {
+        for group in omega_groups(u128::from(value) + 1) {
+            write_wide(writer, group, bit_length(group))?;
+        }
+        writer.write_bits(0, 1)
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError> {
+        let mut n = 1u128;
+        while reader.read_bits(1)? == 1 {
+            // The next group has `n + 1` bits, and `n` itself is never wider than 65 bits.
+            if n > 64 {
+                return Err(LotusError::InvalidEncoding);
+            }
+            n = 1 << n | read_wide(reader, n as usize)?;
+        }
+        shifted_down(n)
+    }
+
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError> {
+        let groups = omega_groups(u128::from(value) + 1);
+        Ok(1 + groups.into_iter().map(bit_length).sum::<usize>())
+    }
+}
+
+/// Fibonacci numbers 1, 2, 3, 5, ... up to the last one not above `2^64`.
+const FIBONACCI: [u128; 92] = {
+    let mut digits = [1u128; 92];
+    digits[1] = 2;
+    let mut index = 2;
+    while index < digits.len() {
+        digits[index] = digits[index - 1] + digits[index - 2];
+        index += 1;
+    }
+    digits
+};
+
+/// The Fibonacci codeword of a positive `n`, right-aligned, with its length.
+fn fibonacci_codeword(mut n: u128) -> (u128, usize) {
+    let top = FIBONACCI.partition_point(|&digit| digit <= n) - 1;
+    // Digits are written least significant first, so digit `index` sits `top + 1 - index` bits
+    // above the final one.
+    let mut codeword = 1;
+    for index in (0..=top).rev() {
+        if FIBONACCI[index] <= n {
+            n -= FIBONACCI[index];
+            codeword |= 1 << (top + 1 - index);
+        }
+    }
+    (codeword, top + 2)
+}
+
+impl IntegerCodec for Fibonacci {
+    fn name(&self) -> String {
+        "fibonacci".to_string()
+    }
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError> {
+        let (codeword, len) = fibonacci_codeword(u128::from(value) + 1);
+        write_wide(writer, codeword, len)
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError> {
+        let (mut n, mut previous) = (0u128, false);
+        for index in 0..=FIBONACCI.len() {
+            let bit = reader.read_bits(1)? == 1;
+            if bit && previous {
+                return shifted_down(n);
+            }
+            if bit {
+                // A one past the largest digit can only be the terminator, checked above.
+                n += *FIBONACCI.get(index).ok_or(LotusError::InvalidEncoding)?;
+            }
+            previous = bit;
+        }
+        Err(LotusError::InvalidEncoding)
+    }
+
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError> {
+        Ok(fibonacci_codeword(u128::from(value) + 1).1)
+    }
+}
+
+fn check_parameter(k: u32) -> Result<usize, LotusError> {
+    if k > 63 {
+        return Err(LotusError::InvalidEncoding);
+    }
+    Ok(k as usize)
+}
+
+impl IntegerCodec for ExpGolomb {
+    fn name(&self) -> String {
+        format!("exp-golomb-{}", self.k)
+    }
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError> {
+        let k = check_parameter(self.k)?;
+        let shifted = u128::from(value) + (1 << k);
+        let len = bit_length(shifted);
+        writer.write_bits(0, len - k - 1)?;
+        write_wide(writer, shifted, len)
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError> {
+        let k = check_parameter(self.k)?;
+        let zeros = read_run(reader, 0, 64 - k as u64)? as usize;
+        let shifted = 1 << (zeros + k) | read_wide(reader, zeros + k)?;
+        u64::try_from(shifted - (1 << k)).map_err(|_| LotusError::ValueTooLarge)
+    }
+
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError> {
+        let k = check_parameter(self.k)?;
+        Ok(2 * bit_length(u128::from(value) + (1 << k)) - k - 1)
+    }
+}
+
+impl GolombRice {
+    fn quotient(&self, value: u64) -> Result<(usize, u64), LotusError> {
+        let k = check_parameter(self.k)?;
+        let quotient = value >> k;
+        if quotient > GOLOMB_RICE_MAX_QUOTIENT {
+            return Err(LotusError::ValueTooLarge);
+        }
+        Ok((k, quotient))
+    }
+}
+
+impl IntegerCodec for GolombRice {
+    fn name(&self) -> String {
+        format!("rice-{}", self.k)
+    }
+
+    fn encode(&self, writer: &mut BitWriter, value: u64) -> Result<(), LotusError> {
+        let (k, mut quotient) = self.quotient(value)?;
+        while quotient >= 64 {
+            writer.write_bits(u64::MAX, 64)?;
+            quotient -= 64;
+        }
+        writer.write_bits(((1 << quotient) - 1) << 1, quotient as usize + 1)?;
+        writer.write_bits(value, k)
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u64, LotusError> {
+        let k = check_parameter(self.k)?;
+        let quotient = read_run(reader, 1, GOLOMB_RICE_MAX_QUOTIENT)?;
+        let value = u128::from(quotient) << k | u128::from(reader.read_bits(k)?);
+        u64::try_from(value).map_err(|_| LotusError::ValueTooLarge)
+    }
+
+    fn encoded_bits(&self, value: u64) -> Result<usize, LotusError> {
+        let (k, quotient) = self.quotient(value)?;
+        Ok(quotient as usize + 1 + k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LOTUS_J3D1;
+
+    fn codecs() -> Vec<Box<dyn IntegerCodec>> {
+        vec![
+            Box::new(LotusCodec::new(LOTUS_J3D1.0, LOTUS_J3D1.1)),
+            Box::new(Leb128),
+            Box::new(EliasGamma),
+            Box::new(EliasDelta),
+            Box::new(EliasOmega),
+            Box::new(Fibonacci),
+            Box::new(ExpGolomb { k: 0 }),
+            Box::new(ExpGolomb { k: 5 }),
+            Box::new(GolombRice { k: 60 }),
+        ]
+    }
+
+    fn bitstring(codec: &dyn IntegerCodec, value: u64) -> String {
+        let mut writer = BitWriter::new();
+        codec.encode(&mut writer, value).unwrap();
+        let len = writer.bits_written();
+        let bytes = writer.into_bytes();
+        let mut reader = BitReader::new(&bytes);
+        (0..len)
+            .map(|_| char::from(b'0' + reader.read_bits(1).unwrap() as u8))
+            .collect()
+    }
+
+    #[test]
+    fn known_codewords() {
+        assert_eq!(bitstring(&EliasGamma, 0), "1");
+        assert_eq!(bitstring(&EliasGamma, 4), "00101");
+        assert_eq!(bitstring(&EliasDelta, 1), "0100");
+        assert_eq!(bitstring(&EliasDelta, 16), "001010001");
+        assert_eq!(bitstring(&EliasOmega, 0), "0");
+        assert_eq!(bitstring(&EliasOmega, 1), "100");
+        assert_eq!(bitstring(&EliasOmega, 15), "10100100000");
+        assert_eq!(bitstring(&Fibonacci, 0), "11");
+        assert_eq!(bitstring(&Fibonacci, 10), "001011");
+        assert_eq!(bitstring(&ExpGolomb { k: 1 }, 3), "0101");
+        assert_eq!(bitstring(&GolombRice { k: 2 }, 9), "11001");
+        assert_eq!(bitstring(&Leb128, 300), "1010110000000010");
+        assert!(FIBONACCI[91] <= 1 << 64 && FIBONACCI[91] + FIBONACCI[90] > 1 << 64);
+        assert_eq!(elias_gamma_bits(u64::MAX), 129);
+        assert_eq!(elias_delta_bits(u64::MAX), 64 + elias_gamma_bits(64));
+    }
+
+    #[test]
+    fn every_codec_round_trips_with_exact_lengths() {
+        let mut values: Vec<u64> = (0..300).collect();
+        for shift in 1..64 {
+            values.extend([(1 << shift) - 1, 1 << shift, (1 << shift) + 1]);
+        }
+        values.push(u64::MAX);
+        for codec in codecs() {
+            let mut writer = BitWriter::new();
+            for &value in &values {
+                let before = writer.bits_written();
+                codec.encode(&mut writer, value).unwrap();
+                let bits = writer.bits_written() - before;
+                assert_eq!(
+                    codec.encoded_bits(value),
+                    Ok(bits),
+                    "{} {value}",
+                    codec.name()
+                );
+            }
+            let bytes = writer.into_bytes();
+            let mut reader = BitReader::new(&bytes);
+            for &value in &values {
+                assert_eq!(codec.decode(&mut reader), Ok(value), "{}", codec.name());
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range() {
+        let rice = GolombRice { k: 0 };
+        assert_eq!(
+            rice.encode(&mut BitWriter::new(), 1 << 20),
+            Err(LotusError::ValueTooLarge)
+        );
+        assert_eq!(
+            rice.decode(&mut BitReader::new(&[0xff; 9000])),
+            Err(LotusError::InvalidEncoding)
+        );
+        assert_eq!(
+            ExpGolomb { k: 64 }.encoded_bits(0),
+            Err(LotusError::InvalidEncoding)
+        );
+        // 2^64 + 1 codes one past u64::MAX.
+        let mut writer = BitWriter::new();
+        gamma_write(&mut writer, (1 << 64) + 2).unwrap();
+        let bytes = writer.into_bytes();
+        assert_eq!(
+            EliasGamma.decode(&mut BitReader::new(&bytes)),
+            Err(LotusError::ValueTooLarge)
+        );
+        assert_eq!(
+            EliasGamma.decode(&mut BitReader::new(&[0; 9])),
+            Err(LotusError::InvalidEncoding)
+        );
+        assert_eq!(
+            Fibonacci.decode(&mut BitReader::new(&[0x55; 16])),
+            Err(LotusError::InvalidEncoding)
+        );
+        assert_eq!(
+            Leb128.decode(&mut BitReader::new(&[0x80, 0x00])),
+            Err(LotusError::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,522 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn lotus(args: &[&str], stdin: &[u8]) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lotus"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Commands that reject their arguments exit without reading stdin.
+    if let Err(err) = child.stdin.take().unwrap().write_all(stdin) {
+        assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cli");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+#[test]
+fn pack_unpack_round_trip() {
+    let text: String = (0..2_000u64)
+        .map(|i| format!("{}\n", i * i % 997))
+        .collect();
+    for codec in ["plain", "delta", "for", "rle"] {
+        let packed = scratch(&format!("round_trip_{codec}.lotus"));
+        let packed = packed.to_str().unwrap();
+        let out = lotus(
+            &["pack", "-o", packed, "--config", "j2d1", "--codec", codec],
+            text.as_bytes(),
+        );
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let summary = String::from_utf8(out.stderr).unwrap();
+        assert!(summary.starts_with("packed 2000 values (J2D1"), "{summary}");
+        assert!(summary.contains("bits/value"), "{summary}");
+
+        let out = lotus(&["unpack", packed, "--config", "J2D1"], b"");
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        assert_eq!(String::from_utf8(out.stdout).unwrap(), text, "{codec}");
+    }
+}
+
+#[test]
+fn pack_is_smaller_than_text_and_rejects_bad_input() {
+    let text: String = (0..1_000u64).map(|i| format!("{}\n", 1_000 + i)).collect();
+    let out = lotus(&["pack"], text.as_bytes());
+    assert!(out.status.success());
+    assert!(out.stdout.len() * 2 < text.len());
+
+    let out = lotus(&["pack", "--config", "J9D1"], b"1\n");
+    assert!(!out.status.success());
+    let out = lotus(&["pack"], b"1\nfive\n");
+    assert!(!out.status.success());
+
+    let packed = scratch("mismatch.lotus");
+    let packed = packed.to_str().unwrap();
+    assert!(lotus(&["pack", "-o", packed], b"7\n").status.success());
+    let out = lotus(&["unpack", packed, "--config", "J3D1"], b"");
+    assert!(!out.status.success());
+}
+
+#[test]
+fn pack_attributes_rejected_deltas_to_their_line() {
+    let text = ["1\n".repeat(4_095), "100000\n".into(), "1\n".repeat(12_288)].concat();
+    let args = ["pack", "--config", "J1D1", "--codec", "delta"];
+    let out = lotus(&args, text.as_bytes());
+    assert_eq!(out.status.code(), Some(4));
+    let message = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        message.contains("line 4096: `100000`: value exceeds"),
+        "{message}"
+    );
+
+    let packed = scratch("skip_delta.lotus");
+    let packed = packed.to_str().unwrap();
+    let out = lotus(
+        &[&args[..], &["--skip-invalid", "-o", packed]].concat(),
+        text.as_bytes(),
+    );
+    let message = String::from_utf8(out.stderr).unwrap();
+    assert_eq!(out.status.code(), Some(0), "{message}");
+    assert!(message.contains("skipped line 4096: `100000`"), "{message}");
+    assert!(message.contains("packed 16383 values"), "{message}");
+    let out = lotus(&["unpack", packed], b"");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "1\n".repeat(16_383));
+}
+
+#[test]
+fn analyze_reports_costs_and_recommendation() {
+    let text: String = (0..500u64).map(|i| format!("{}\n", i % 50)).collect();
+    let out = lotus(&["analyze", "--format", "json"], text.as_bytes());
+    assert!(out.status.success());
+    let json = String::from_utf8(out.stdout).unwrap();
+    assert!(json.starts_with("{\"count\":500,\"distinct\":50,\"min\":0,\"max\":49,"));
+    assert!(json.contains("\"name\":\"J8D4\""), "{json}");
+    assert!(json.contains("\"name\":\"elias-delta\""), "{json}");
+    assert!(json.contains("\"recommended\":\"J"), "{json}");
+
+    let out = lotus(&["analyze"], text.as_bytes());
+    let table = String::from_utf8(out.stdout).unwrap();
+    assert!(table.contains("entropy:"), "{table}");
+    assert!(table.contains("\nrecommended: J"), "{table}");
+
+    let out = lotus(&["analyze", "-f", "json"], b"");
+    assert!(
+        String::from_utf8(out.stdout)
+            .unwrap()
+            .ends_with("\"recommended\":null}\n")
+    );
+}
+
+#[test]
+fn explain_encoded_and_hex_input() {
+    let out = lotus(&["explain", "--config", "J3D2", "42"], b"");
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(text.starts_with("42 under J3D2: 13 bits"), "{text}");
+    assert!(text.contains("jumpstarter  001"), "{text}");
+    assert!(text.contains("tier 2       000"), "{text}");
+    assert!(
+        text.contains("8..13  payload      01101  value 42"),
+        "{text}"
+    );
+
+    let out = lotus(&["explain", "--config", "J3D2", "--hex", "3068"], b"");
+    assert!(out.status.success());
+    let decoded = String::from_utf8(out.stdout).unwrap();
+    assert!(decoded.starts_with(&text), "{decoded}");
+    assert!(decoded.ends_with("3 of 16 input bits follow the codeword\n"));
+
+    assert!(!lotus(&["explain", "--hex", "00"], b"").status.success());
+    assert!(!lotus(&["explain", "--hex", "zz"], b"").status.success());
+}
+
+#[test]
+fn encode_decode_formats_round_trip() {
+    let values = "0\n1\n300\n70000\n18446744073709551615\n";
+    for format in ["hex", "binary", "base64", "raw", "json"] {
+        let encoded = lotus(
+            &["encode", "-j", "3", "--output-format", format],
+            values.as_bytes(),
+        );
+        assert!(encoded.status.success(), "{format}");
+        let decoded = lotus(
+            &["decode", "-j", "3", "--input-format", format],
+            &encoded.stdout,
+        );
+        assert!(
+            decoded.status.success(),
+            "{format}: {}",
+            String::from_utf8_lossy(&decoded.stderr)
+        );
+        assert_eq!(
+            String::from_utf8(decoded.stdout).unwrap(),
+            values,
+            "{format}"
+        );
+    }
+
+    let binary = lotus(&["encode", "--output-format", "binary"], b"42\n");
+    assert_eq!(String::from_utf8(binary.stdout).unwrap(), "1000001101\n");
+    // A bitstring must be exactly one codeword.
+    assert!(
+        !lotus(&["decode", "--input-format", "binary"], b"10000011010\n")
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn value_formats() {
+    let out = lotus(
+        &["encode", "--input-format", "hex", "--output-format", "json"],
+        b"0x2a\nff\n",
+    );
+    let out = lotus(
+        &[
+            "decode",
+            "--input-format",
+            "json",
+            "--output-format",
+            "binary",
+        ],
+        &out.stdout,
+    );
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "101010\n11111111\n");
+
+    let with_header: &[u8] = b"id,\"label, quoted\",count\n1,\"a,b\",5\n2,c,10\n";
+    let without_header: &[u8] = b"1,\"a,b\",5\n2,c,10\n";
+    for (column, csv) in [("count", with_header), ("3", without_header)] {
+        let args = ["encode", "--input-format", "csv", "--column", column];
+        let encoded = lotus(&args, csv);
+        assert!(encoded.status.success(), "{column}");
+        let decoded = lotus(&["decode", "--output-format", "csv"], &encoded.stdout);
+        assert_eq!(String::from_utf8(decoded.stdout).unwrap(), "5\n10\n");
+    }
+    // CSV output reads back with the default column.
+    let decoded = lotus(&["decode", "--output-format", "csv"], b"50\n9978\n");
+    let encoded = lotus(&["encode", "--input-format", "csv"], &decoded.stdout);
+    assert!(
+        encoded.status.success(),
+        "{}",
+        String::from_utf8_lossy(&encoded.stderr)
+    );
+    assert_eq!(String::from_utf8(encoded.stdout).unwrap(), "50\n9978\n");
+    let out = lotus(
+        &["encode", "--input-format", "csv", "--column", "missing"],
+        with_header,
+    );
+    assert!(!out.status.success());
+
+    let out = lotus(&["decode", "--output-format", "json"], b"");
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "[]\n");
+}
+
+#[test]
+fn benchmark_reports_sizes_and_throughput() {
+    let out = lotus(
+        &[
+            "benchmark",
+            "--dist",
+            "geometric:0.1",
+            "--dist",
+            "uniform:40",
+            "--count",
+            "2000",
+            "--repeat",
+            "1",
+            "--config",
+            "J2D1,J3D1",
+            "--format",
+            "csv",
+        ],
+        b"",
+    );
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let csv = String::from_utf8(out.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = csv.lines().map(|line| line.split(',').collect()).collect();
+    assert_eq!(rows[0][..4], ["workload", "codec", "count", "total_bits"]);
+    assert_eq!(rows.len(), 1 + 2 * 10);
+    let j3d1 = rows
+        .iter()
+        .find(|row| row[..2] == ["\"geometric:0.1\"", "J3D1"])
+        .unwrap();
+    assert!(j3d1[5].parse::<f64>().unwrap() > 0.0);
+    // Baselines are real codecs too, so they are timed; Golomb parameters follow the mean.
+    let omega = rows
+        .iter()
+        .find(|row| row[..2] == ["\"uniform:40\"", "elias-omega"])
+        .unwrap();
+    assert!(omega[7].parse::<f64>().unwrap() > 0.0);
+    assert!(rows.iter().any(|row| row[1] == "rice-38"), "{csv}");
+    // J2D1 cannot code 40-bit values: no size and no timings.
+    let j2d1 = rows
+        .iter()
+        .find(|row| row[..2] == ["\"uniform:40\"", "J2D1"])
+        .unwrap();
+    assert!(j2d1[3..].iter().all(|cell| cell.is_empty()));
+
+    let text: String = (0..300u64).map(|i| format!("{}\n", i * 7)).collect();
+    let path = scratch("benchmark_input.txt");
+    std::fs::write(&path, text).unwrap();
+    let out = lotus(
+        &[
+            "benchmark",
+            "--input",
+            path.to_str().unwrap(),
+            "--repeat",
+            "1",
+            "-f",
+            "json",
+        ],
+        b"",
+    );
+    assert!(out.status.success());
+    let json = String::from_utf8(out.stdout).unwrap();
+    assert!(
+        json.contains("\"codec\":\"leb128\",\"count\":300"),
+        "{json}"
+    );
+    assert!(!json.contains("uniform"), "{json}");
+
+    // File names become CSV fields, quoted with any quotes doubled.
+    let path = scratch("benchmark,\"odd\".txt");
+    std::fs::write(&path, "1\n2\n").unwrap();
+    let path = path.to_str().unwrap();
+    let out = lotus(
+        &["benchmark", "--input", path, "--repeat", "1", "-f", "csv"],
+        b"",
+    );
+    let csv = String::from_utf8(out.stdout).unwrap();
+    let quoted = format!("\"{}\",leb128,2,", path.replace('"', "\"\""));
+    assert!(csv.contains(&quoted), "{csv}");
+
+    assert!(
+        !lotus(&["benchmark", "--dist", "zipf:0:1"], b"")
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn table_lists_envelope_and_summary() {
+    let out = lotus(&["table", "--config", "J2D1"], b"");
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(
+        text.contains("\n      2                                          1..=4  2"),
+        "{text}"
+    );
+    assert!(text.ends_with("J2D1 max value: 536870908\n"), "{text}");
+
+    let out = lotus(&["table", "--all"], b"");
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(text.lines().count(), 1 + 8 * 4, "{text}");
+    assert!(text.contains("\nJ3D1        64"), "{text}");
+    assert!(text.contains("18446744073709551615"), "{text}");
+}
+
+#[test]
+fn selfcheck_reports_code_space() {
+    let out = lotus(
+        &["selfcheck", "--config", "J1D1,J2D1", "--max-bits", "12"],
+        b"",
+    );
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(
+        text.contains("\nJ1D1           29          0..=28"),
+        "{text}"
+    );
+    assert!(text.contains("\nJ2D1          253"), "{text}");
+    assert!(
+        text.ends_with("up to 12 bits is prefix-free and round-trips\n"),
+        "{text}"
+    );
+
+    let out = lotus(&["selfcheck"], b"");
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().lines().count(),
+        2 + 8 * 4
+    );
+    assert_eq!(
+        lotus(&["selfcheck", "--max-bits", "25"], b"").status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn verify_reports_corruption_with_exit_status() {
+    let text: String = (0..1_000u64).map(|i| format!("{i}\n")).collect();
+    let packed = scratch("verify.lotus");
+    let packed = packed.to_str().unwrap();
+    assert!(
+        lotus(&["pack", "-o", packed], text.as_bytes())
+            .status
+            .success()
+    );
+    let out = lotus(&["verify", packed], b"");
+    assert_eq!(out.status.code(), Some(0));
+    let report = String::from_utf8(out.stdout).unwrap();
+    assert!(report.contains("ok, 1000 values"), "{report}");
+
+    let mut bytes = std::fs::read(packed).unwrap();
+    bytes[40] ^= 0xff;
+    let corrupt = scratch("verify_corrupt.lotus");
+    std::fs::write(&corrupt, &bytes).unwrap();
+    let out = lotus(&["verify", corrupt.to_str().unwrap()], b"");
+    assert_eq!(out.status.code(), Some(4));
+    let report = String::from_utf8(out.stderr).unwrap();
+    assert!(report.contains("corrupt at bit "), "{report}");
+    assert!(report.contains("(value "), "{report}");
+
+    std::fs::write(&corrupt, &bytes[..20]).unwrap();
+    let out = lotus(&["verify", corrupt.to_str().unwrap()], b"");
+    assert_eq!(out.status.code(), Some(4));
+
+    let out = lotus(&["verify", scratch("missing.lotus").to_str().unwrap()], b"");
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        String::from_utf8(out.stderr)
+            .unwrap()
+            .starts_with("lotus: cannot open")
+    );
+}
+
+#[test]
+fn convert_leb128_round_trip() {
+    let mut varints = Vec::new();
+    for i in 0..3_000u64 {
+        lotus::leb128_write(&mut varints, i * 37 % 100_000);
+    }
+    let packed = scratch("convert.lotus");
+    let packed = packed.to_str().unwrap();
+    let out = lotus(
+        &["convert", "--from", "leb128", "--to", "lotus", "-o", packed],
+        &varints,
+    );
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let summary = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        summary.starts_with("converted 3000 values (leb128 -> lotus, J2D1"),
+        "{summary}"
+    );
+    assert!(summary.ends_with("smaller\n"), "{summary}");
+    let out = lotus(
+        &["convert", packed, "--from", "lotus", "--to", "leb128"],
+        b"",
+    );
+    assert!(out.status.success());
+    assert_eq!(out.stdout, varints);
+
+    let mut signed = Vec::new();
+    for value in [-1i64, 0, 64, i64::MIN, i64::MAX] {
+        lotus::sleb128_write(&mut signed, value);
+    }
+    let out = lotus(
+        &[
+            "convert", "--from", "sleb128", "--to", "lotus", "-c", "J3D1",
+        ],
+        &signed,
+    );
+    assert!(out.status.success());
+    let out = lotus(
+        &["convert", "--from", "lotus", "--to", "sleb128"],
+        &out.stdout,
+    );
+    assert_eq!(out.stdout, signed);
+
+    let out = lotus(
+        &["convert", "--from", "leb128", "--to", "lotus"],
+        &[0x05, 0x80, 0x00],
+    );
+    assert!(!out.status.success());
+    let message = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        message.contains("varint 1 at byte 1 is overlong"),
+        "{message}"
+    );
+}
+
+#[test]
+fn line_errors_and_exit_codes() {
+    let text = b"1\n\n# comment\nfive\n3\n99999999999\n";
+    let out = lotus(&["encode"], text);
+    assert_eq!(out.status.code(), Some(3));
+    let message = String::from_utf8(out.stderr).unwrap();
+    assert_eq!(message, "lotus: line 4: invalid integer `five`\n");
+
+    let out = lotus(&["encode", "--skip-invalid"], text);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(String::from_utf8(out.stdout).unwrap().lines().count(), 2);
+    let message = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        message.contains("skipped line 4: invalid integer `five`"),
+        "{message}"
+    );
+    assert!(
+        message.contains("skipped line 6: `99999999999`: value exceeds"),
+        "{message}"
+    );
+
+    let out = lotus(&["encode", "--strict"], b"99999999999\n");
+    assert_eq!(out.status.code(), Some(4));
+    let out = lotus(&["decode", "--skip-invalid"], b"50\nzz\n00\n58\n");
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "1\n3\n");
+    let out = lotus(&["decode"], b"50\n\n00\n");
+    assert_eq!(out.status.code(), Some(4));
+    assert!(
+        String::from_utf8(out.stderr)
+            .unwrap()
+            .contains("line 3: `00`")
+    );
+    let out = lotus(&["encode", "--strict", "--skip-invalid"], b"1\n");
+    assert_eq!(out.status.code(), Some(2));
+    // A bad configuration fails up front, even when invalid lines would be skipped.
+    for args in [["encode", "-j", "9"], ["decode", "-t", "0"]] {
+        let out = lotus(&[&args[..], &["--skip-invalid"]].concat(), b"1\n2\n");
+        assert_eq!(out.status.code(), Some(1), "{args:?}");
+        assert!(out.stdout.is_empty());
+    }
+}
+
+#[test]
+fn io_errors_name_the_file() {
+    let missing = scratch("missing.txt");
+    let _ = std::fs::remove_file(&missing);
+    let missing = missing.to_str().unwrap();
+    for args in [["pack", missing], ["unpack", missing], ["analyze", missing]] {
+        let out = lotus(&args, b"");
+        assert_eq!(out.status.code(), Some(1), "{args:?}");
+        let message = String::from_utf8(out.stderr).unwrap();
+        assert!(message.contains(missing), "{args:?}: {message}");
+    }
+}
@@ -0,0 +1,76 @@
+use std::io::Cursor;
+
+use lotus::{ContainerReader, ContainerWriter, LOTUS_J2D1, LotusError, SequenceCodec};
+
+fn container(codec: SequenceCodec) -> (Vec<u64>, Vec<u8>) {
+    let values: Vec<u64> = (0..300).map(|i| (i * 37) % 1_000 + i / 50).collect();
+    let (j_bits, tiers) = LOTUS_J2D1;
+    let mut writer = ContainerWriter::with_block_len(Vec::new(), j_bits, tiers, codec, 64).unwrap();
+    writer.extend(&values).unwrap();
+    (values, writer.finish().unwrap())
+}
+
+fn read(bytes: Vec<u8>) -> Result<Vec<u64>, LotusError> {
+    ContainerReader::new(Cursor::new(bytes))?.read_all()
+}
+
+#[test]
+fn every_single_bit_flip_is_detected() {
+    for codec in [
+        SequenceCodec::Plain,
+        SequenceCodec::Delta,
+        SequenceCodec::Rle,
+    ] {
+        let (values, bytes) = container(codec);
+        assert_eq!(read(bytes.clone()).unwrap(), values);
+        for index in 0..bytes.len() {
+            for bit in 0..8 {
+                let mut corrupt = bytes.clone();
+                corrupt[index] ^= 1 << bit;
+                assert!(
+                    read(corrupt).is_err(),
+                    "{codec:?}: flip of bit {bit} in byte {index} went unnoticed"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn block_corruption_reports_checksum_mismatch() {
+    let (_, mut bytes) = container(SequenceCodec::Plain);
+    bytes[20] ^= 0xff;
+    let mut reader = ContainerReader::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(reader.read_block(0), Err(LotusError::ChecksumMismatch));
+    assert!(reader.read_block(1).is_ok());
+}
+
+#[test]
+fn truncation_and_foreign_files_rejected() {
+    let (_, bytes) = container(SequenceCodec::Plain);
+    for len in [0, 3, 5, 12, bytes.len() / 2, bytes.len() - 1] {
+        assert!(read(bytes[..len].to_vec()).is_err(), "truncated to {len}");
+    }
+    let mut appended = bytes.clone();
+    appended.push(0);
+    assert!(read(appended).is_err());
+    assert!(read(b"not a lotus container at all".to_vec()).is_err());
+
+    let mut future = bytes;
+    future[4] = 2;
+    assert!(matches!(read(future), Err(LotusError::Message(_))));
+}
+
+#[test]
+fn unfinished_container_rejected() {
+    let (j_bits, tiers) = LOTUS_J2D1;
+    let mut sink = Vec::new();
+    {
+        let mut writer =
+            ContainerWriter::with_block_len(&mut sink, j_bits, tiers, SequenceCodec::Plain, 8)
+                .unwrap();
+        writer.extend(&[1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+    }
+    assert!(!sink.is_empty());
+    assert!(read(sink).is_err());
+}
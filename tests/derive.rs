@@ -0,0 +1,192 @@
+#![cfg(feature = "derive")]
+
+use std::collections::BTreeMap;
+
+use lotus::{
+    BitReader, BitWriter, LOTUS_J1D2, LOTUS_J2D1, LOTUS_J3D1, LotusDecode, LotusEncode, LotusError,
+    lotus_read_u64, lotus_write_u64, zigzag_encode,
+};
+
+#[derive(Debug, PartialEq, LotusEncode, LotusDecode)]
+struct Reading {
+    sensor: u32,
+    #[lotus(zigzag)]
+    delta: i32,
+    #[lotus(config = "J1D2")]
+    small: u8,
+    #[lotus(raw = 12)]
+    flags: u16,
+    #[lotus(skip)]
+    cached: Option<String>,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, LotusEncode, LotusDecode)]
+struct Pair(#[lotus(zigzag)] i64, #[lotus(raw = 64)] u64);
+
+#[derive(Debug, PartialEq, LotusEncode, LotusDecode)]
+struct Marker;
+
+fn seven() -> u32 {
+    7
+}
+
+#[derive(Debug, PartialEq, LotusEncode, LotusDecode)]
+enum Message {
+    Ping,
+    Data(Vec<u8>),
+    Move {
+        #[lotus(zigzag)]
+        dx: i16,
+        #[lotus(zigzag)]
+        dy: i16,
+        #[lotus(skip, default = "seven")]
+        speed: u32,
+    },
+    Batch {
+        readings: BTreeMap<u32, Reading>,
+    },
+}
+
+#[derive(Debug, PartialEq, LotusEncode, LotusDecode)]
+struct Wrapper<T> {
+    inner: T,
+    count: usize,
+}
+
+#[derive(Debug, PartialEq, LotusEncode, LotusDecode)]
+enum Shadowing {
+    Variant { writer: u8, j_bits: u8, tiers: u8 },
+}
+
+fn round_trip<T: LotusEncode + LotusDecode + PartialEq + std::fmt::Debug>(value: &T) {
+    for (j_bits, tiers) in [LOTUS_J2D1, LOTUS_J3D1] {
+        let bytes = value.lotus_encode_to_vec(j_bits, tiers).unwrap();
+        let decoded = T::lotus_decode_from_slice(&bytes, j_bits, tiers).unwrap();
+        assert_eq!(&decoded, value);
+    }
+}
+
+fn reading() -> Reading {
+    Reading {
+        sensor: 42,
+        delta: -17,
+        small: 3,
+        flags: 0xabc,
+        cached: None,
+        tags: vec!["a".into(), "bc".into()],
+    }
+}
+
+#[test]
+fn structs_round_trip() {
+    round_trip(&reading());
+    round_trip(&Pair(-123_456, u64::MAX));
+    round_trip(&Marker);
+    round_trip(&Wrapper {
+        inner: Pair(-1, 1),
+        count: 3,
+    });
+    round_trip(&Shadowing::Variant {
+        writer: 1,
+        j_bits: 2,
+        tiers: 3,
+    });
+}
+
+#[test]
+fn enums_round_trip() {
+    round_trip(&Message::Ping);
+    round_trip(&Message::Data(vec![1, 2, 255]));
+    round_trip(&Message::Move {
+        dx: -300,
+        dy: 12,
+        speed: 7,
+    });
+    round_trip(&Message::Batch {
+        readings: [(1, reading()), (9, reading())].into_iter().collect(),
+    });
+}
+
+#[test]
+fn layout_matches_hand_written_encoding() {
+    let (j_bits, tiers) = LOTUS_J2D1;
+    let mut value = reading();
+    value.cached = Some("not encoded".into());
+    let derived = value.lotus_encode_to_vec(j_bits, tiers).unwrap();
+
+    let mut writer = BitWriter::new();
+    lotus_write_u64(&mut writer, 42, j_bits, tiers).unwrap();
+    lotus_write_u64(&mut writer, zigzag_encode(-17), j_bits, tiers).unwrap();
+    lotus_write_u64(&mut writer, 3, LOTUS_J1D2.0, LOTUS_J1D2.1).unwrap();
+    writer.write_bits(0xabc, 12).unwrap();
+    value.tags.lotus_encode(&mut writer, j_bits, tiers).unwrap();
+    assert_eq!(derived, writer.into_bytes());
+
+    let decoded = Reading::lotus_decode_from_slice(&derived, j_bits, tiers).unwrap();
+    assert_eq!(decoded.cached, None);
+}
+
+#[test]
+fn variant_index_is_declaration_order() {
+    let (j_bits, tiers) = LOTUS_J3D1;
+    let bytes = Message::Data(vec![])
+        .lotus_encode_to_vec(j_bits, tiers)
+        .unwrap();
+    let mut reader = BitReader::new(&bytes);
+    assert_eq!(lotus_read_u64(&mut reader, j_bits, tiers).unwrap(), 1);
+
+    let mut writer = BitWriter::new();
+    lotus_write_u64(&mut writer, 4, j_bits, tiers).unwrap();
+    assert_eq!(
+        Message::lotus_decode_from_slice(&writer.into_bytes(), j_bits, tiers),
+        Err(LotusError::InvalidEncoding)
+    );
+}
+
+#[test]
+fn out_of_range_fields_are_rejected() {
+    #[derive(Debug, LotusEncode)]
+    struct Narrow {
+        #[lotus(raw = 4)]
+        nibble: u8,
+        #[lotus(zigzag)]
+        wide: u64,
+    }
+    let (j_bits, tiers) = LOTUS_J3D1;
+    let too_wide = Narrow {
+        nibble: 16,
+        wide: 0,
+    };
+    assert_eq!(
+        too_wide.lotus_encode_to_vec(j_bits, tiers),
+        Err(LotusError::ValueTooLarge)
+    );
+    let unsigned = Narrow {
+        nibble: 15,
+        wide: u64::MAX,
+    };
+    assert_eq!(
+        unsigned.lotus_encode_to_vec(j_bits, tiers),
+        Err(LotusError::ValueTooLarge)
+    );
+
+    #[derive(Debug, PartialEq, LotusDecode)]
+    struct Small {
+        #[lotus(zigzag)]
+        value: i8,
+    }
+    let mut writer = BitWriter::new();
+    lotus_write_u64(&mut writer, zigzag_encode(1_000), j_bits, tiers).unwrap();
+    assert_eq!(
+        Small::lotus_decode_from_slice(&writer.into_bytes(), j_bits, tiers),
+        Err(LotusError::ValueTooLarge)
+    );
+}
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/derive/pass/*.rs");
+    t.compile_fail("tests/ui/derive/fail/*.rs");
+}
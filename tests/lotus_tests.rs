@@ -1,4 +1,7 @@
-use lotus::{LOTUS_J1D2, LOTUS_J2D1, LOTUS_J3D1, LotusError, lotus_decode_u64, lotus_encode_u64};
+use lotus::{
+    LOTUS_J1D2, LOTUS_J2D1, LOTUS_J3D1, LotusError, leb128_encode, lotus_decode_u64,
+    lotus_encode_u64,
+};
 #[cfg(feature = "bigint")]
 use lotus::lotus_encode_biguint;
 
@@ -31,22 +34,6 @@ fn maximal_edges() {
 
 #[test]
 fn leb128_comparison() {
-    fn leb128_encode(mut value: u64) -> Vec<u8> {
-        let mut out = Vec::new();
-        loop {
-            let mut byte = (value & 0x7f) as u8;
-            value >>= 7;
-            if value != 0 {
-                byte |= 0x80;
-                out.push(byte);
-            } else {
-                out.push(byte);
-                break;
-            }
-        }
-        out
-    }
-
     let sample = [0u64, 1, 2, 127, 128, 4096, 1_000_000];
     for value in sample {
         let lotus = lotus_encode_u64(value, LOTUS_J2D1.0, LOTUS_J2D1.1).unwrap();
@@ -99,3 +86,27 @@ mod bigint_tests {
         );
     }
 }
+
+#[test]
+fn packed_stream_matches_encoded_bits() {
+    use lotus::{BitReader, BitWriter, lotus_encoded_bits, lotus_read_u64, lotus_write_u64};
+
+    let values = [0u64, 7, 300, 65_535, u32::MAX as u64];
+    let (j_bits, tiers) = LOTUS_J3D1;
+    let mut writer = BitWriter::new();
+    for value in values {
+        lotus_write_u64(&mut writer, value, j_bits, tiers).unwrap();
+    }
+    let expected_bits: usize = values
+        .iter()
+        .map(|v| lotus_encoded_bits(*v, j_bits, tiers).unwrap())
+        .sum();
+    assert_eq!(writer.bits_written(), expected_bits);
+
+    let bytes = writer.into_bytes();
+    let mut reader = BitReader::new(&bytes);
+    for value in values {
+        assert_eq!(lotus_read_u64(&mut reader, j_bits, tiers).unwrap(), value);
+    }
+    assert_eq!(reader.bits_consumed(), expected_bits);
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "serde")]
+
+use lotus::{
+    LOTUS_J2D1, LotusError, from_slice, from_slice_with_config, to_vec, to_vec_with_config,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Kind {
+    Heartbeat,
+    Reading(f64),
+    Range { low: i32, high: i32 },
+    Pair(u8, char),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    id: u64,
+    offset: i64,
+    name: String,
+    tags: Vec<u16>,
+    parent: Option<u32>,
+    flags: (bool, bool),
+    kinds: Vec<Kind>,
+    attrs: BTreeMap<String, i8>,
+    wide: u128,
+    ratio: f32,
+}
+
+fn sample() -> Message {
+    Message {
+        id: u64::MAX,
+        offset: -42,
+        name: "sensor-Ω".to_string(),
+        tags: vec![1, 2, 300],
+        parent: None,
+        flags: (true, false),
+        kinds: vec![
+            Kind::Heartbeat,
+            Kind::Reading(-0.0),
+            Kind::Range { low: -5, high: 7 },
+            Kind::Pair(9, '🪷'),
+        ],
+        attrs: BTreeMap::from([("a".to_string(), -128), ("b".to_string(), 127)]),
+        wide: u128::MAX - 1,
+        ratio: f32::NAN,
+    }
+}
+
+#[test]
+fn derive_round_trip() {
+    let message = sample();
+    let bytes = to_vec(&message).unwrap();
+    let decoded: Message = from_slice(&bytes).unwrap();
+    assert_eq!(decoded.ratio.to_bits(), message.ratio.to_bits());
+    assert_eq!(
+        Message {
+            ratio: 0.0,
+            ..decoded
+        },
+        Message {
+            ratio: 0.0,
+            ..message
+        }
+    );
+}
+
+#[test]
+fn config_is_selectable() {
+    let values: Vec<u32> = (0..64).collect();
+    let (j_bits, tiers) = LOTUS_J2D1;
+    let small = to_vec_with_config(&values, j_bits, tiers).unwrap();
+    let decoded: Vec<u32> = from_slice_with_config(&small, j_bits, tiers).unwrap();
+    assert_eq!(decoded, values);
+    assert!(small.len() < to_vec(&values).unwrap().len());
+}
+
+#[test]
+fn small_values_pack_below_a_byte() {
+    let bytes = to_vec(&(true, 0u8, -1i16, Option::<u8>::None)).unwrap();
+    assert!(bytes.len() <= 3, "{} bytes", bytes.len());
+}
+
+#[test]
+fn truncated_and_trailing_input_rejected() {
+    let bytes = to_vec(&sample()).unwrap();
+    assert!(from_slice::<Message>(&bytes[..bytes.len() / 2]).is_err());
+
+    let mut padded = to_vec(&7u64).unwrap();
+    padded.push(0);
+    assert!(matches!(
+        from_slice::<u64>(&padded),
+        Err(LotusError::Message(_))
+    ));
+}
+
+#[test]
+fn narrowing_overflow_is_value_too_large() {
+    let bytes = to_vec(&300u64).unwrap();
+    assert_eq!(
+        from_slice::<u8>(&bytes).unwrap_err(),
+        LotusError::ValueTooLarge
+    );
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "tokio")]
+
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use lotus::{LOTUS_J2D1, LotusLengthCodec};
+use tokio::io::{AsyncWriteExt, duplex};
+use tokio_util::codec::{Framed, FramedRead};
+
+#[tokio::test]
+async fn frames_round_trip_over_duplex() {
+    let (client, server) = duplex(64);
+    let codec = LotusLengthCodec::with_config(LOTUS_J2D1.0, LOTUS_J2D1.1);
+    let mut sender = Framed::new(client, codec.clone());
+    let mut receiver = Framed::new(server, codec);
+
+    let payloads: Vec<Vec<u8>> = vec![
+        b"hello".to_vec(),
+        Vec::new(),
+        (0..=255).collect(),
+        vec![0xaa; 10_000],
+    ];
+    let expected = payloads.clone();
+    let send = tokio::spawn(async move {
+        for payload in payloads {
+            sender.send(Bytes::from(payload)).await.unwrap();
+        }
+    });
+    for payload in expected {
+        let frame = receiver.next().await.unwrap().unwrap();
+        assert_eq!(frame.to_vec(), payload);
+    }
+    send.await.unwrap();
+}
+
+#[tokio::test]
+async fn header_split_across_writes() {
+    let (mut client, server) = duplex(64);
+    let mut reader = FramedRead::new(server, LotusLengthCodec::new());
+
+    let mut wire = bytes::BytesMut::new();
+    tokio_util::codec::Encoder::encode(
+        &mut LotusLengthCodec::new(),
+        Bytes::from(vec![5u8; 300]),
+        &mut wire,
+    )
+    .unwrap();
+    let writer = tokio::spawn(async move {
+        for byte in wire {
+            client.write_all(&[byte]).await.unwrap();
+            client.flush().await.unwrap();
+            tokio::task::yield_now().await;
+        }
+    });
+    let frame = reader.next().await.unwrap().unwrap();
+    assert_eq!(frame.to_vec(), vec![5u8; 300]);
+    writer.await.unwrap();
+    assert!(reader.next().await.is_none());
+}
+
+#[tokio::test]
+async fn truncated_stream_is_an_error() {
+    let (mut client, server) = duplex(64);
+    let mut reader = FramedRead::new(server, LotusLengthCodec::new());
+    client.write_all(&[0xff]).await.unwrap();
+    drop(client);
+    assert!(reader.next().await.unwrap().is_err());
+}
@@ -0,0 +1,9 @@
+use lotus::LotusEncode;
+
+#[derive(LotusEncode)]
+struct Bad {
+    #[lotus(config = "J9D1")]
+    value: u32,
+}
+
+fn main() {}
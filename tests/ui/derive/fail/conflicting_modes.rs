@@ -0,0 +1,9 @@
+use lotus::LotusEncode;
+
+#[derive(LotusEncode)]
+struct Bad {
+    #[lotus(zigzag, raw = 8)]
+    value: i32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use lotus::LotusEncode;
+
+struct Opaque;
+
+#[derive(LotusEncode)]
+struct Bad {
+    inner: Opaque,
+}
+
+fn main() {}
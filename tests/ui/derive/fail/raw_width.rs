@@ -0,0 +1,9 @@
+use lotus::LotusDecode;
+
+#[derive(LotusDecode)]
+struct Bad {
+    #[lotus(raw = 65)]
+    value: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use lotus::LotusEncode;
+
+#[derive(LotusEncode)]
+union Bad {
+    a: u32,
+    b: f32,
+}
+
+fn main() {}
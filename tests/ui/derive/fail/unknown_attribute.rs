@@ -0,0 +1,9 @@
+use lotus::LotusEncode;
+
+#[derive(LotusEncode)]
+struct Bad {
+    #[lotus(varint)]
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+use lotus::{LotusDecode, LotusEncode};
+
+#[derive(LotusEncode, LotusDecode)]
+struct Header {
+    #[lotus(config = "j3d1")]
+    id: u64,
+    #[lotus(zigzag, config = "J2D1")]
+    offset: i8,
+    #[lotus(raw = 1)]
+    flag: u8,
+    #[lotus(skip, default = "Vec::new")]
+    scratch: Vec<u8>,
+}
+
+#[derive(LotusEncode, LotusDecode)]
+enum Never {}
+
+#[derive(LotusEncode, LotusDecode)]
+enum Tree<T> {
+    Leaf(T),
+    Node(Box<Tree<T>>, Box<Tree<T>>),
+}
+
+fn main() {
+    let header = Header {
+        id: 1,
+        offset: -1,
+        flag: 1,
+        scratch: Vec::new(),
+    };
+    let bytes = header.lotus_encode_to_vec(2, 1).unwrap();
+    let _ = Header::lotus_decode_from_slice(&bytes, 2, 1).unwrap();
+    let _ = Never::lotus_decode_from_slice(&bytes, 2, 1);
+    let tree = Tree::Node(Box::new(Tree::Leaf(1u8)), Box::new(Tree::Leaf(2u8)));
+    let _ = tree.lotus_encode_to_vec(2, 1).unwrap();
+}